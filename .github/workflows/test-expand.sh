@@ -1,6 +1,6 @@
 #!/bin/bash
 
-TEST_MODULES=(convolution dsu fenwicktree lazysegtree math maxflow mincostflow modint scc segtree string twosat --all)
+TEST_MODULES=(convexhulltrick convolution dirichlet dsu dualsegtree dynamicsegtree fenwicktree hld lazysegtree linear_recurrence math maxflow mincostflow modint persistentsegtree poly scc segtree segtree2d segtreebeats sparsetable string twosat universal_euclidean waveletmatrix --all)
 TMP_PATH=$(mktemp -d)
 # shellcheck disable=SC2164
 SCRIPT_DIR="$(cd "$(dirname "$0")"; pwd)"
@@ -12,7 +12,7 @@ for MODULE in "${TEST_MODULES[@]}" ;do
      python3 "$SCRIPT_DIR/../../expand.py" "$MODULE" > "$TMP_PATH/$TEST_FILE"
      echo Output "$(wc -c < "$TMP_PATH/$TEST_FILE")" Bytes
      echo "$FILE_HEAD" >> "$TMP_PATH/$TEST_FILE"
-     if ! rustc -A warnings "$TMP_PATH/$TEST_FILE";
+     if ! rustc -A warnings -o "$TMP_PATH/test" "$TMP_PATH/$TEST_FILE";
      then
         echo Error compiling for "$MODULE"
         exit 1
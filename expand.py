@@ -7,18 +7,31 @@ import subprocess
 
 usage = '''Usage:expand.py [options] <output modules>
 Output Modules:
+    convexhulltrick
     convolution
+    dirichlet
     dsu
+    dualsegtree
+    dynamicsegtree
     fenwicktree
+    hld
     lazysegtree
+    linear_recurrence
     math
     maxflow
     mincostflow
     modint
+    persistentsegtree
+    poly
     scc
     segtree
+    segtree2d
+    segtreebeats
+    sparsetable
     string
     twosat
+    universal_euclidean
+    waveletmatrix
 
 You can select multiple modules for <output modules>
     e.g.)expand.py math segtree
@@ -31,20 +44,33 @@ Options:
 '''
 output_header = '//https://github.com/rust-lang-ja/ac-library-rs\n'
 opt_list = ['help', 'all', 'output=']
-output_list_all = ('convolution', 'dsu', 'fenwicktree', 'lazysegtree', 'math',
-                   'maxflow',  'mincostflow', 'modint', 'scc',  'segtree',
-                   'string', 'twosat',
+output_list_all = ('convexhulltrick', 'convolution', 'dirichlet', 'dsu', 'dualsegtree', 'dynamicsegtree', 'fenwicktree', 'hld', 'lazysegtree',
+                   'linear_recurrence', 'math',
+                   'maxflow',  'mincostflow', 'modint', 'persistentsegtree', 'poly', 'scc',  'segtree',
+                   'segtree2d', 'segtreebeats', 'sparsetable', 'string', 'twosat', 'universal_euclidean', 'waveletmatrix',
                    'internal_bit', 'internal_math', 'internal_queue',
                    'internal_scc', 'internal_type_traits',)
-dependency_list = {'convolution': ('internal_bit', 'modint',),
+dependency_list = {'convexhulltrick': ('internal_type_traits',),
+                   'convolution': ('internal_bit', 'modint',),
+                   'dirichlet': ('modint',),
+                   'dualsegtree': ('internal_bit', 'segtree',),
+                   'dynamicsegtree': ('internal_type_traits', 'segtree',),
+                   'hld': ('lazysegtree', 'segtree',),
                    'lazysegtree': ('internal_bit', 'segtree'),
-                   'math': ('internal_math',),
+                   'linear_recurrence': ('convolution', 'modint',),
+                   'math': ('internal_math', 'internal_type_traits',),
                    'maxflow': ('internal_type_traits', 'internal_queue',),
                    'mincostflow': ('internal_type_traits',),
-                   'modint': ('internal_math',),
+                   'modint': ('internal_math', 'internal_type_traits',),
+                   'persistentsegtree': ('segtree',),
+                   'poly': ('convolution', 'modint',),
                    'scc': ('internal_scc',),
                    'segtree': ('internal_bit', 'internal_type_traits',),
-                   'twosat': ('internal_scc',), }
+                   'segtree2d': ('internal_bit', 'segtree',),
+                   'segtreebeats': ('internal_type_traits', 'lazysegtree', 'segtree',),
+                   'sparsetable': ('internal_type_traits', 'segtree',),
+                   'twosat': ('internal_scc',),
+                   'universal_euclidean': ('internal_math', 'segtree',), }
 src_path = 'src/'
 output_path = None
 
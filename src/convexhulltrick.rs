@@ -0,0 +1,591 @@
+//! Minimum of linear functions: Li Chao tree and monotone convex hull trick.
+//!
+//! A line $y = ax + b$ is given as `(a, b)`.
+//! Both structures answer minimum queries; to get the maximum, insert $(-a, -b)$ and negate the answer.
+//! The values are evaluated in `i128`, so the coordinates are limited to the [`LineValue`] types, for which $ax + b$ does not overflow.
+
+use crate::internal_type_traits::Integral;
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+use std::ops::{Bound, RangeBounds};
+
+/// The types of the slopes, the intercepts and the $x$-coordinates: `i8`, `i16`, `i32`, `i64`, `u8`, `u16` and `u32`.
+///
+/// The values are evaluated in `i128`, which cannot hold $ax + b$ for the wider types, so they are rejected at compile time.
+///
+/// ```compile_fail
+/// use ac_library::LiChaoTree;
+///
+/// let _ = LiChaoTree::<u64>::with_range(0, 10);
+/// ```
+pub trait LineValue: Integral + Into<i128> + sealed::Sealed {}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+macro_rules! impl_line_value {
+    ($($ty:ty),*) => {
+        $(
+            impl sealed::Sealed for $ty {}
+            impl LineValue for $ty {}
+        )*
+    };
+}
+
+impl_line_value!(i8, i16, i32, i64, u8, u16, u32);
+
+/// A Li Chao tree over a fixed set of $x$-coordinates or over an interval of integers.
+///
+/// # Complexity
+///
+/// - $O(\log m)$ for each `add_line` and `query`
+/// - $O(\log^2 m)$ for each `add_segment`
+///
+/// where $m$ is the size of the domain.
+/// It allocates nodes lazily, so the memory usage is $O(\min(m, q \log m))$ for $q$ insertions.
+///
+/// # Example
+///
+/// ```
+/// use ac_library::LiChaoTree;
+///
+/// let mut cht = LiChaoTree::<i64>::with_range(-1_000_000_000, 1_000_000_001);
+/// cht.add_line(2, 3);
+/// cht.add_line(-1, 0);
+/// cht.add_segment(0, -10, 5..=7);
+/// assert_eq!(cht.query(-2), Some(-1));
+/// assert_eq!(cht.query(6), Some(-10));
+/// assert_eq!(cht.query(1_000_000_000), Some(-1_000_000_000));
+///
+/// let mut cht = LiChaoTree::<i64>::new(&[1, 3, 5]);
+/// assert_eq!(cht.query(3), None);
+/// cht.add_segment(1, 0, 4..);
+/// assert_eq!(cht.query(3), None);
+/// assert_eq!(cht.query(5), Some(5));
+/// ```
+#[derive(Clone, Debug)]
+pub struct LiChaoTree<T> {
+    domain: Domain<T>,
+    nodes: Vec<Node<T>>,
+}
+
+#[derive(Clone, Debug)]
+enum Domain<T> {
+    // The sorted distinct coordinates.
+    Points(Vec<T>),
+    // `lo`, `lo + 1`, ..., `hi - 1`
+    Interval(i128, i128),
+}
+
+#[derive(Clone, Debug)]
+struct Node<T> {
+    line: Option<(T, T)>,
+    children: [usize; 2],
+}
+
+const NIL: usize = !0;
+
+impl<T: LineValue> LiChaoTree<T> {
+    /// Creates a tree for queries at `xs`.
+    pub fn new(xs: &[T]) -> Self {
+        let mut xs = xs.to_vec();
+        xs.sort_unstable();
+        xs.dedup();
+        Self::from_domain(Domain::Points(xs))
+    }
+
+    /// Creates a tree for queries at the integers in $[\mathrm{lo}, \mathrm{hi})$.
+    ///
+    /// # Constraints
+    ///
+    /// - $\mathrm{lo} \leq \mathrm{hi}$
+    pub fn with_range(lo: T, hi: T) -> Self {
+        assert!(lo <= hi);
+        Self::from_domain(Domain::Interval(lo.into(), hi.into()))
+    }
+
+    /// Adds the line $y = ax + b$.
+    pub fn add_line(&mut self, a: T, b: T) {
+        let m = self.domain_len();
+        if m > 0 {
+            self.add_line_at(0, 0, m, (a, b));
+        }
+    }
+
+    /// Adds the segment $y = ax + b$ for $x$ in `xs`.
+    pub fn add_segment<R>(&mut self, a: T, b: T, xs: R)
+    where
+        R: RangeBounds<T>,
+    {
+        let (l, r) = self.to_positions(&xs);
+        if l < r {
+            self.add_segment_at(0, 0, self.domain_len(), l, r, (a, b));
+        }
+    }
+
+    /// Returns the minimum value of the lines and the segments at $x$, or `None` if none of them covers $x$.
+    ///
+    /// # Panics
+    ///
+    /// Panics if $x$ is not in the domain.
+    pub fn query(&self, x: T) -> Option<i128> {
+        let p = match &self.domain {
+            Domain::Points(xs) => xs.binary_search(&x).expect("x is not in the domain") as i128,
+            Domain::Interval(lo, hi) => {
+                let x = x.into();
+                assert!(*lo <= x && x < *hi, "x is not in the domain");
+                x - lo
+            }
+        };
+        let x = x.into();
+        let (mut k, mut l, mut r) = (0, 0, self.domain_len());
+        let mut ret: Option<i128> = None;
+        while k != NIL {
+            if let Some(line) = self.nodes[k].line {
+                let y = eval(line, x);
+                ret = Some(ret.map_or(y, |z| z.min(y)));
+            }
+            let m = (l + r) / 2;
+            if p < m {
+                r = m;
+                k = self.nodes[k].children[0];
+            } else {
+                l = m;
+                k = self.nodes[k].children[1];
+            }
+        }
+        ret
+    }
+
+    fn from_domain(domain: Domain<T>) -> Self {
+        LiChaoTree {
+            domain,
+            nodes: vec![Node {
+                line: None,
+                children: [NIL; 2],
+            }],
+        }
+    }
+
+    fn domain_len(&self) -> i128 {
+        match &self.domain {
+            Domain::Points(xs) => xs.len() as i128,
+            Domain::Interval(lo, hi) => hi - lo,
+        }
+    }
+
+    fn x(&self, p: i128) -> i128 {
+        match &self.domain {
+            Domain::Points(xs) => xs[p as usize].into(),
+            Domain::Interval(lo, _) => lo + p,
+        }
+    }
+
+    fn to_positions<R: RangeBounds<T>>(&self, range: &R) -> (i128, i128) {
+        let (l, r) = match &self.domain {
+            Domain::Points(xs) => {
+                let l = match range.start_bound() {
+                    Bound::Included(l) => xs.partition_point(|x| x < l),
+                    Bound::Excluded(l) => xs.partition_point(|x| x <= l),
+                    Bound::Unbounded => 0,
+                };
+                let r = match range.end_bound() {
+                    Bound::Included(r) => xs.partition_point(|x| x <= r),
+                    Bound::Excluded(r) => xs.partition_point(|x| x < r),
+                    Bound::Unbounded => xs.len(),
+                };
+                (l as i128, r as i128)
+            }
+            Domain::Interval(lo, hi) => {
+                let l = match range.start_bound() {
+                    Bound::Included(&l) => l.into(),
+                    Bound::Excluded(&l) => l.into() + 1,
+                    Bound::Unbounded => *lo,
+                };
+                let r = match range.end_bound() {
+                    Bound::Included(&r) => r.into() + 1,
+                    Bound::Excluded(&r) => r.into(),
+                    Bound::Unbounded => *hi,
+                };
+                (l.max(*lo) - lo, r.min(*hi) - lo)
+            }
+        };
+        (l, r.max(l))
+    }
+
+    fn child(&mut self, k: usize, i: usize) -> usize {
+        if self.nodes[k].children[i] == NIL {
+            self.nodes[k].children[i] = self.nodes.len();
+            self.nodes.push(Node {
+                line: None,
+                children: [NIL; 2],
+            });
+        }
+        self.nodes[k].children[i]
+    }
+
+    fn add_line_at(&mut self, mut k: usize, mut l: i128, mut r: i128, mut line: (T, T)) {
+        loop {
+            let cur = match self.nodes[k].line {
+                Some(cur) => cur,
+                None => {
+                    self.nodes[k].line = Some(line);
+                    return;
+                }
+            };
+            let m = (l + r) / 2;
+            let (xl, xm) = (self.x(l), self.x(m));
+            let left_better = eval(line, xl) < eval(cur, xl);
+            let mid_better = eval(line, xm) < eval(cur, xm);
+            if mid_better {
+                self.nodes[k].line = Some(line);
+                line = cur;
+            }
+            if r - l == 1 {
+                return;
+            }
+            if left_better != mid_better {
+                k = self.child(k, 0);
+                r = m;
+            } else {
+                k = self.child(k, 1);
+                l = m;
+            }
+        }
+    }
+
+    fn add_segment_at(&mut self, k: usize, l: i128, r: i128, a: i128, b: i128, line: (T, T)) {
+        if b <= l || r <= a {
+            return;
+        }
+        if a <= l && r <= b {
+            self.add_line_at(k, l, r, line);
+            return;
+        }
+        let m = (l + r) / 2;
+        let left = self.child(k, 0);
+        self.add_segment_at(left, l, m, a, b, line);
+        let right = self.child(k, 1);
+        self.add_segment_at(right, m, r, a, b, line);
+    }
+}
+
+/// A convex hull trick for lines added in the monotone order of the slopes.
+///
+/// It keeps the lower envelope in a deque sorted by the slopes, so each line must have the smallest or the largest slope so far.
+///
+/// # Complexity
+///
+/// - $O(1)$ amortized for each `add_line` and `query_monotone`
+/// - $O(\log n)$ for each `query`
+///
+/// # Example
+///
+/// ```
+/// use ac_library::MonotoneCht;
+///
+/// let mut cht = MonotoneCht::<i64>::new();
+/// cht.add_line(0, 3);
+/// cht.add_line(-1, 6);
+/// cht.add_line(2, 0);
+/// assert_eq!(cht.query(-5), Some(-10));
+/// assert_eq!(cht.query(2), Some(3));
+/// assert_eq!(cht.query(5), Some(1));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct MonotoneCht<T> {
+    // The slopes are strictly decreasing.
+    lines: VecDeque<(T, T)>,
+}
+
+impl<T: LineValue> MonotoneCht<T> {
+    pub fn new() -> Self {
+        MonotoneCht {
+            lines: VecDeque::new(),
+        }
+    }
+
+    /// Adds the line $y = ax + b$.
+    ///
+    /// # Constraints
+    ///
+    /// - $a$ is at most or at least the slopes of all the lines added so far
+    ///
+    /// # Panics
+    ///
+    /// Panics if the above constraints are not satisfied.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(1)$ amortized
+    pub fn add_line(&mut self, a: T, b: T) {
+        let line = (a, b);
+        let (front, back) = match (self.lines.front(), self.lines.back()) {
+            (Some(&front), Some(&back)) => (front, back),
+            _ => {
+                self.lines.push_back(line);
+                return;
+            }
+        };
+        let d = &mut self.lines;
+        if a <= back.0 {
+            if a == back.0 {
+                if back.1 <= b {
+                    return;
+                }
+                d.pop_back();
+            }
+            while d.len() >= 2 && is_unnecessary(d[d.len() - 2], d[d.len() - 1], line) {
+                d.pop_back();
+            }
+            d.push_back(line);
+        } else {
+            assert!(front.0 <= a, "the slopes must be monotone");
+            if a == front.0 {
+                if front.1 <= b {
+                    return;
+                }
+                d.pop_front();
+            }
+            while d.len() >= 2 && is_unnecessary(line, d[0], d[1]) {
+                d.pop_front();
+            }
+            d.push_front(line);
+        }
+    }
+
+    /// Returns the minimum value of the lines at $x$, or `None` if there are no lines.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(\log n)$
+    pub fn query(&self, x: T) -> Option<i128> {
+        let x = x.into();
+        let (mut l, mut r) = (0, self.lines.len().checked_sub(1)?);
+        // The values decrease up to the optimal line and then increase.
+        while l < r {
+            let m = (l + r) / 2;
+            if eval(self.lines[m], x) > eval(self.lines[m + 1], x) {
+                l = m + 1;
+            } else {
+                r = m;
+            }
+        }
+        Some(eval(self.lines[l], x))
+    }
+
+    /// Same as [`query`], but $x$ must be at least any $x$ given to this method before.
+    ///
+    /// It discards the lines that are no longer needed for such $x$.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(1)$ amortized
+    ///
+    /// [`query`]: #method.query
+    pub fn query_monotone(&mut self, x: T) -> Option<i128> {
+        let x = x.into();
+        while self.lines.len() >= 2 && eval(self.lines[0], x) >= eval(self.lines[1], x) {
+            self.lines.pop_front();
+        }
+        self.lines.front().map(|&line| eval(line, x))
+    }
+}
+
+fn eval<T: LineValue>((a, b): (T, T), x: i128) -> i128 {
+    a.into() * x + b.into()
+}
+
+/// Returns whether $l_2$ is never below both $l_1$ and $l_3$, where $a_1 > a_2 > a_3$.
+fn is_unnecessary<T: LineValue>(l1: (T, T), l2: (T, T), l3: (T, T)) -> bool {
+    let (a1, b1) = (l1.0.into(), l1.1.into());
+    let (a2, b2) = (l2.0.into(), l2.1.into());
+    let (a3, b3) = (l3.0.into(), l3.1.into());
+    // $l_1$ and $l_2$ cross at $x_{12} = (b_2 - b_1) / (a_1 - a_2)$, and $l_2$ and $l_3$ at $x_{23}$.
+    cmp_fraction(b2 - b1, a1 - a2, b3 - b2, a2 - a3) != Ordering::Less
+}
+
+/// Compares $p_1 / q_1$ and $p_2 / q_2$ for $q_1, q_2 > 0$ without overflow.
+fn cmp_fraction(mut p1: i128, mut q1: i128, mut p2: i128, mut q2: i128) -> Ordering {
+    let mut reversed = false;
+    let ord = loop {
+        let (f1, f2) = (p1.div_euclid(q1), p2.div_euclid(q2));
+        if f1 != f2 {
+            break f1.cmp(&f2);
+        }
+        let (r1, r2) = (p1 - f1 * q1, p2 - f2 * q2);
+        if r1 == 0 || r2 == 0 {
+            break r1.cmp(&r2);
+        }
+        // $r_1 / q_1$ vs $r_2 / q_2$ is the reverse of $q_1 / r_1$ vs $q_2 / r_2$.
+        p1 = q1;
+        q1 = r1;
+        p2 = q2;
+        q2 = r2;
+        reversed = !reversed;
+    };
+    if reversed {
+        ord.reverse()
+    } else {
+        ord
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    #[test]
+    fn test_li_chao_tree() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..20 {
+            let xs = (0..30)
+                .map(|_| rng.gen_range(-50, 50))
+                .collect::<Vec<i64>>();
+            let mut points = LiChaoTree::new(&xs);
+            let mut interval = LiChaoTree::with_range(-50, 50);
+            let mut segments = vec![];
+            for _ in 0..100 {
+                let (a, b) = (rng.gen_range(-20, 20), rng.gen_range(-1000, 1000));
+                let (l, r) = (rng.gen_range(-60, 60), rng.gen_range(-60, 60));
+                if rng.gen() {
+                    points.add_line(a, b);
+                    interval.add_line(a, b);
+                    segments.push((a, b, i64::MIN, i64::MAX));
+                } else {
+                    points.add_segment(a, b, l..=r);
+                    interval.add_segment(a, b, l..=r);
+                    segments.push((a, b, l, r));
+                }
+                for x in -50..50 {
+                    let expected = segments
+                        .iter()
+                        .filter(|&&(_, _, l, r)| l <= x && x <= r)
+                        .map(|&(a, b, _, _)| i128::from(a * x + b))
+                        .min();
+                    assert_eq!(interval.query(x), expected);
+                    if xs.contains(&x) {
+                        assert_eq!(points.query(x), expected);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_li_chao_tree_large() {
+        let lines = [(i64::MAX, i64::MAX), (i64::MIN, i64::MIN), (1, i64::MIN)];
+        let mut cht = LiChaoTree::with_range(i64::MIN, i64::MAX);
+        for &(a, b) in &lines {
+            cht.add_line(a, b);
+        }
+        for &x in &[i64::MIN, -1, 0, 1, i64::MAX - 1] {
+            let expected = lines
+                .iter()
+                .map(|&(a, b)| i128::from(a) * i128::from(x) + i128::from(b))
+                .min();
+            assert_eq!(cht.query(x), expected);
+        }
+
+        let mut cht = LiChaoTree::new(&[u32::MAX, 0]);
+        cht.add_line(u32::MAX, u32::MAX);
+        assert_eq!(cht.query(u32::MAX), Some(i128::from(u32::MAX) << 32));
+
+        // `u64` is rejected at compile time (see `LineValue`), but its values can be given as `i64`.
+        let mut cht = MonotoneCht::new();
+        cht.add_line(i64::from(u32::MAX), i64::MAX);
+        cht.add_line(0, i64::MAX - 1);
+        assert_eq!(
+            cht.query(i64::from(u32::MAX)),
+            Some(i128::from(i64::MAX - 1))
+        );
+        assert_eq!(
+            cht.query(i64::MIN),
+            Some(i128::from(u32::MAX) * i128::from(i64::MIN) + i128::from(i64::MAX))
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_li_chao_tree_out_of_domain() {
+        LiChaoTree::new(&[1, 3]).query(2);
+    }
+
+    #[test]
+    fn test_monotone_cht() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..100 {
+            let mut cht = MonotoneCht::new();
+            let mut lines = vec![];
+            let (mut lo, mut hi) = (0, 0);
+            let mut x_min = -100;
+            for _ in 0..50 {
+                let a = if lines.is_empty() {
+                    0
+                } else if rng.gen() {
+                    lo -= rng.gen_range(0, 3);
+                    lo
+                } else {
+                    hi += rng.gen_range(0, 3);
+                    hi
+                };
+                let b = rng.gen_range(-100, 100);
+                cht.add_line(a, b);
+                lines.push((a, b));
+                let naive = |x: i64| lines.iter().map(|&(a, b)| i128::from(a * x + b)).min();
+                for x in x_min..100 {
+                    assert_eq!(cht.query(x), naive(x));
+                }
+                x_min += rng.gen_range(0, 3);
+                assert_eq!(cht.query_monotone(x_min), naive(x_min));
+            }
+        }
+    }
+
+    #[test]
+    fn test_monotone_cht_large() {
+        let lines = [
+            (0, 0),
+            (i64::MIN, i64::MAX),
+            (i64::MAX, i64::MIN),
+            (i64::MIN, i64::MAX - 1),
+            (i64::MIN, i64::MAX),
+            (i64::MAX, i64::MIN + 1),
+        ];
+        let mut cht = MonotoneCht::new();
+        for (i, &(a, b)) in lines.iter().enumerate() {
+            cht.add_line(a, b);
+            for &x in &[i64::MIN, -2, -1, 0, 1, 2, i64::MAX] {
+                let expected = lines[..=i]
+                    .iter()
+                    .map(|&(a, b)| i128::from(a) * i128::from(x) + i128::from(b))
+                    .min();
+                assert_eq!(cht.query(x), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_cmp_fraction() {
+        for p1 in -20..20 {
+            for q1 in 1..10 {
+                for p2 in -20..20 {
+                    for q2 in 1..10 {
+                        assert_eq!(cmp_fraction(p1, q1, p2, q2), (p1 * q2).cmp(&(p2 * q1)));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_monotone_cht_non_monotone() {
+        let mut cht = MonotoneCht::new();
+        cht.add_line(0, 0);
+        cht.add_line(2, 0);
+        cht.add_line(1, 0);
+    }
+}
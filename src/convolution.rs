@@ -10,6 +10,13 @@
 //!
 //! - Separated the overloaded `convolution` into `convolution<_>` and `convolution_raw<_, _>`.
 //! - Renamed `convolution_ll` to `convolution_i64`.
+//! - Added [`OnlineConvolution`], [`convolution_2d`], [`convolution_nd`], [`multivariate_convolution`] and [`multivariate_convolution_total_degree`], which are not in the original ACL.
+//!
+//! [`OnlineConvolution`]: ./struct.OnlineConvolution.html
+//! [`convolution_2d`]: ./fn.convolution_2d.html
+//! [`convolution_nd`]: ./fn.convolution_nd.html
+//! [`multivariate_convolution`]: ./fn.multivariate_convolution.html
+//! [`multivariate_convolution_total_degree`]: ./fn.multivariate_convolution_total_degree.html
 
 macro_rules! modulus {
     ($($name:ident),*) => {
@@ -302,6 +309,487 @@ pub fn convolution_i64(a: &[i64], b: &[i64]) -> Vec<i64> {
         .collect()
 }
 
+/// Calculates the two-dimensional $(+, \times)$ convolution in $\mathbb{Z}/p\mathbb{Z}$.
+///
+/// Given an $H_a \times W_a$ grid $a$ and an $H_b \times W_b$ grid $b$, returns the $(H_a + H_b - 1) \times (W_a + W_b - 1)$ grid $c$ defined by
+///
+/// \\[
+///   c_{i, j} = \sum_{k = 0}^i \sum_{l = 0}^j a_{k, l} b_{i - k, j - l}.
+/// \\]
+///
+/// Returns a empty `Vec` if `a` or `b` has no element.
+///
+/// # Constraints
+///
+/// - Each of `a` and `b` is rectangular.
+/// - `M::VALUE` satisfies the constraints of [`convolution`] for the length $(H_a + H_b - 1)(W_a + W_b - 1)$.
+///
+/// # Panics
+///
+/// Panics if `a` or `b` is not rectangular.
+///
+/// # Complexity
+///
+/// - $O(n \log n)$ where $n = (H_a + H_b)(W_a + W_b)$.
+///
+/// # Example
+///
+/// ```
+/// use ac_library::{convolution::convolution_2d, ModInt998244353 as Mint};
+///
+/// let a = vec![vec![Mint::new(1), Mint::new(2)], vec![Mint::new(3), Mint::new(4)]];
+/// let b = vec![vec![Mint::new(1), Mint::new(1)]];
+/// assert_eq!(
+///     convolution_2d(&a, &b),
+///     [
+///         [Mint::new(1), Mint::new(3), Mint::new(2)],
+///         [Mint::new(3), Mint::new(7), Mint::new(4)],
+///     ],
+/// );
+/// ```
+///
+/// [`convolution`]: ./fn.convolution.html
+pub fn convolution_2d<M: Modulus>(
+    a: &[Vec<StaticModInt<M>>],
+    b: &[Vec<StaticModInt<M>>],
+) -> Vec<Vec<StaticModInt<M>>> {
+    let flatten = |a: &[Vec<StaticModInt<M>>]| {
+        let w = a.first().map_or(0, Vec::len);
+        assert!(a.iter().all(|row| row.len() == w), "not rectangular");
+        (a.concat(), [a.len(), w])
+    };
+    let (fa, a_shape) = flatten(a);
+    let (fb, b_shape) = flatten(b);
+    if fa.is_empty() || fb.is_empty() {
+        return vec![];
+    }
+    let w = a_shape[1] + b_shape[1] - 1;
+    convolution_nd(&fa, &a_shape, &fb, &b_shape, None)
+        .chunks(w)
+        .map(<[_]>::to_vec)
+        .collect()
+}
+
+/// Calculates the $d$-dimensional $(+, \times)$ convolution in $\mathbb{Z}/p\mathbb{Z}$.
+///
+/// `a` and `b` are arrays of shape `a_shape` and `b_shape` flattened in row-major order (the last index varies fastest).
+/// The result is the array $c_\gamma = \sum_{\alpha + \beta = \gamma} a_\alpha b_\beta$ of shape `shape` in the same order.
+/// If `shape` is `None`, it is $(s_{a, 0} + s_{b, 0} - 1, \ldots, s_{a, d - 1} + s_{b, d - 1} - 1)$, i.e. the whole result.
+/// Otherwise each dimension is truncated (or padded with zeros) to `shape`, which is cheaper than truncating the whole result afterwards.
+///
+/// Returns a empty `Vec` if `a` or `b` is empty.
+///
+/// # Constraints
+///
+/// - $|a| = \prod$ `a_shape`, $|b| = \prod$ `b_shape`
+/// - `a_shape`, `b_shape` and `shape` have the same length.
+/// - `M::VALUE` satisfies the constraints of [`convolution`] for the length $\prod_i (\min(s_{a, i}, s_i) + \min(s_{b, i}, s_i) - 1)$.
+///
+/// # Panics
+///
+/// Panics if the above constraints are not satisfied.
+///
+/// # Complexity
+///
+/// - $O(n \log n)$ where $n = \prod_i (\min(s_{a, i}, s_i) + \min(s_{b, i}, s_i))$.
+///
+/// # Example
+///
+/// ```
+/// use ac_library::{convolution::convolution_nd, ModInt998244353 as Mint};
+///
+/// // (1 + x + y + xy) * (1 + x + y + xy) truncated to degree 1 for both x and y
+/// let a = [Mint::new(1); 4];
+/// assert_eq!(
+///     convolution_nd(&a, &[2, 2], &a, &[2, 2], Some(&[2, 2])),
+///     [Mint::new(1), Mint::new(2), Mint::new(2), Mint::new(4)],
+/// );
+/// ```
+///
+/// [`convolution`]: ./fn.convolution.html
+pub fn convolution_nd<M: Modulus>(
+    a: &[StaticModInt<M>],
+    a_shape: &[usize],
+    b: &[StaticModInt<M>],
+    b_shape: &[usize],
+    shape: Option<&[usize]>,
+) -> Vec<StaticModInt<M>> {
+    let d = a_shape.len();
+    assert_eq!(d, b_shape.len());
+    assert_eq!(a.len(), a_shape.iter().product::<usize>());
+    assert_eq!(b.len(), b_shape.iter().product::<usize>());
+    if a.is_empty() || b.is_empty() {
+        return vec![];
+    }
+    let shape = match shape {
+        Some(shape) => {
+            assert_eq!(d, shape.len());
+            shape.to_vec()
+        }
+        None => (0..d).map(|i| a_shape[i] + b_shape[i] - 1).collect(),
+    };
+    let n = shape.iter().product::<usize>();
+    if n == 0 {
+        return vec![];
+    }
+
+    // Kronecker substitution. Since every dimension of the embedding is large enough, no carry occurs.
+    let a_trunc = (0..d)
+        .map(|i| cmp::min(a_shape[i], shape[i]))
+        .collect::<Vec<_>>();
+    let b_trunc = (0..d)
+        .map(|i| cmp::min(b_shape[i], shape[i]))
+        .collect::<Vec<_>>();
+    let embed_shape = (0..d)
+        .map(|i| a_trunc[i] + b_trunc[i] - 1)
+        .collect::<Vec<_>>();
+    let embed = |x: &[StaticModInt<M>], x_shape: &[usize], x_trunc: &[usize]| {
+        let mut ret = vec![StaticModInt::raw(0); embed_shape.iter().product()];
+        for (i, &v) in x.iter().enumerate() {
+            if let Some(j) = reindex(i, x_shape, Some(x_trunc), &embed_shape) {
+                ret[j] = v;
+            }
+        }
+        ret
+    };
+    let c = convolution(&embed(a, a_shape, &a_trunc), &embed(b, b_shape, &b_trunc));
+    (0..n)
+        .map(|i| reindex(i, &shape, None, &embed_shape).map_or(StaticModInt::raw(0), |j| c[j]))
+        .collect()
+}
+
+/// Calculates the multivariate power series product in $\mathbb{Z}/p\mathbb{Z}$, truncated to the given shape.
+///
+/// `a` and `b` are arrays of shape `shape` flattened in row-major order (the last index varies fastest).
+/// Returns the array of shape `shape` defined by $c_\gamma = \sum_{\alpha + \beta = \gamma} a_\alpha b_\beta$.
+///
+/// Unlike [`convolution_nd`], the length of the internal convolution is about $2 \prod$ `shape` regardless of the number of dimensions.
+///
+/// # Constraints
+///
+/// - $|a| = |b| = \prod$ `shape`
+/// - `M::VALUE` satisfies the constraints of [`convolution`] for the length $2 |a|$.
+///
+/// # Panics
+///
+/// Panics if $|a| \neq \prod$ `shape` or $|b| \neq \prod$ `shape`.
+///
+/// # Complexity
+///
+/// - $O(k n \log n + k^2 n)$ where $n = \prod$ `shape` and $k = |$`shape`$|$.
+///
+/// # Example
+///
+/// ```
+/// use ac_library::{convolution::multivariate_convolution, ModInt998244353 as Mint};
+///
+/// // (1 + x)(1 + y) = 1 + x + y + xy
+/// let a = [1, 0, 1, 0].map(Mint::new);
+/// let b = [1, 1, 0, 0].map(Mint::new);
+/// assert_eq!(multivariate_convolution(&a, &b, &[2, 2]), [Mint::new(1); 4]);
+/// ```
+///
+/// [`convolution_nd`]: ./fn.convolution_nd.html
+/// [`convolution`]: ./fn.convolution.html
+pub fn multivariate_convolution<M: Modulus>(
+    a: &[StaticModInt<M>],
+    b: &[StaticModInt<M>],
+    shape: &[usize],
+) -> Vec<StaticModInt<M>> {
+    let n = shape.iter().product::<usize>();
+    assert_eq!(a.len(), n);
+    assert_eq!(b.len(), n);
+    if n == 0 {
+        return vec![];
+    }
+
+    // Reference: https://rushcheyo.blog.uoj.ac/blog/6547
+    //
+    // chi(i) = \sum_{j < k - 1} floor(i / stride_j), where stride_j is the stride of the j-th dimension.
+    // For i + j < n, chi(i + j) - chi(i) - chi(j) is the number of carries, which is in [0, k - 1].
+    // Hence i + j is a sum without carries iff chi(i) + chi(j) = chi(i + j) (mod k).
+    let k = cmp::max(shape.len(), 1);
+    let mut strides = vec![1; shape.len()];
+    for i in (1..shape.len()).rev() {
+        strides[i - 1] = strides[i] * shape[i];
+    }
+    let chi = (0..n)
+        .map(|i| {
+            strides[..shape.len().saturating_sub(1)]
+                .iter()
+                .map(|s| i / s)
+                .sum::<usize>()
+                % k
+        })
+        .collect::<Vec<_>>();
+
+    let z = 1 << internal_bit::ceil_pow2((2 * n - 1) as _);
+    let transform = |x: &[StaticModInt<M>]| {
+        (0..k)
+            .map(|t| {
+                let mut y = vec![StaticModInt::raw(0); z];
+                for i in (0..n).filter(|&i| chi[i] == t) {
+                    y[i] = x[i];
+                }
+                butterfly(&mut y);
+                y
+            })
+            .collect::<Vec<_>>()
+    };
+    let (fa, fb) = (transform(a), transform(b));
+
+    let iz = StaticModInt::new(z).inv();
+    let mut c = vec![StaticModInt::raw(0); n];
+    for s in 0..k {
+        let mut y = vec![StaticModInt::raw(0); z];
+        for (t, fa) in fa.iter().enumerate() {
+            let fb = &fb[(s + k - t) % k];
+            for ((y, &x1), &x2) in y.iter_mut().zip(fa).zip(fb) {
+                *y += x1 * x2;
+            }
+        }
+        butterfly_inv(&mut y);
+        for i in (0..n).filter(|&i| chi[i] == s) {
+            c[i] = y[i] * iz;
+        }
+    }
+    c
+}
+
+/// Calculates the multivariate power series product in $\mathbb{Z}/p\mathbb{Z}$, truncated by the total degree.
+///
+/// Same as [`multivariate_convolution`] except that $c_\gamma$ is set to $0$ if $\sum_i \gamma_i$ exceeds `degree`.
+/// Since such $c_\gamma$ with $\sum_i \gamma_i \leq$ `degree` only depend on the terms of $a$ and $b$ of total degree at most `degree`, the other terms of the input are ignored.
+///
+/// # Constraints
+///
+/// - $|a| = |b| = \prod$ `shape`
+/// - `M::VALUE` satisfies the constraints of [`convolution`] for the length $2 |a|$.
+///
+/// # Panics
+///
+/// Panics if $|a| \neq \prod$ `shape` or $|b| \neq \prod$ `shape`.
+///
+/// # Complexity
+///
+/// - $O(k n \log n + k^2 n)$ where $n = \prod$ `shape` and $k = |$`shape`$|$.
+///
+/// # Example
+///
+/// ```
+/// use ac_library::{convolution::multivariate_convolution_total_degree, ModInt998244353 as Mint};
+///
+/// // (1 + x)(1 + y) = 1 + x + y + xy
+/// let a = [1, 0, 1, 0].map(Mint::new);
+/// let b = [1, 1, 0, 0].map(Mint::new);
+/// assert_eq!(
+///     multivariate_convolution_total_degree(&a, &b, &[2, 2], 1),
+///     [1, 1, 1, 0].map(Mint::new),
+/// );
+/// ```
+///
+/// [`multivariate_convolution`]: ./fn.multivariate_convolution.html
+/// [`convolution`]: ./fn.convolution.html
+pub fn multivariate_convolution_total_degree<M: Modulus>(
+    a: &[StaticModInt<M>],
+    b: &[StaticModInt<M>],
+    shape: &[usize],
+    degree: usize,
+) -> Vec<StaticModInt<M>> {
+    let n = shape.iter().product::<usize>();
+    assert_eq!(a.len(), n);
+    assert_eq!(b.len(), n);
+    let total_degree = |mut i: usize| {
+        let mut ret = 0;
+        for &s in shape.iter().rev() {
+            ret += i % s;
+            i /= s;
+        }
+        ret
+    };
+    let truncate = |x: &[StaticModInt<M>]| {
+        x.iter()
+            .enumerate()
+            .map(|(i, &x)| {
+                if total_degree(i) <= degree {
+                    x
+                } else {
+                    StaticModInt::raw(0)
+                }
+            })
+            .collect::<Vec<_>>()
+    };
+    truncate(&multivariate_convolution(&truncate(a), &truncate(b), shape))
+}
+
+/// Maps the flat index `i` of an array of shape `from` to the flat index of the same multi-index in an array of shape `to`.
+///
+/// Returns `None` if the multi-index is out of `to` or `limit`.
+fn reindex(mut i: usize, from: &[usize], limit: Option<&[usize]>, to: &[usize]) -> Option<usize> {
+    let mut j = 0;
+    let mut stride = 1;
+    for d in (0..from.len()).rev() {
+        let x = i % from[d];
+        i /= from[d];
+        if x >= to[d] || limit.map_or(false, |limit| x >= limit[d]) {
+            return None;
+        }
+        j += x * stride;
+        stride *= to[d];
+    }
+    Some(j)
+}
+
+/// Calculates the $(+, \times)$ convolution in $\mathbb{Z}/p\mathbb{Z}$ online.
+///
+/// The $i$-th call of [`push`] receives $a_i$ and $b_i$ and immediately returns $c_i = \sum_{j = 0}^i a_j b_{i - j}$.
+/// Thus $a_i$ and $b_i$ may depend on $c_0, c_1, \ldots, c_{i - 1}$, which is the case for self-referential DPs.
+///
+/// # Constraints
+///
+/// - `M::VALUE` satisfies the constraints of [`convolution`].
+///
+/// # Complexity
+///
+/// - $O(n \log^2 n)$ in total for $n$ calls of [`push`].
+///
+/// # Example
+///
+/// ```
+/// use ac_library::{convolution::OnlineConvolution, ModInt998244353 as Mint};
+///
+/// // Catalan numbers: C_0 = 1, C_{n + 1} = \sum_{i = 0}^n C_i C_{n - i}
+/// let mut conv = OnlineConvolution::new();
+/// let mut catalan = vec![Mint::new(1)];
+/// for i in 0..10 {
+///     let c = conv.push(catalan[i], catalan[i]);
+///     catalan.push(c);
+/// }
+/// assert_eq!(catalan[10], Mint::new(16796));
+/// ```
+///
+/// [`push`]: #method.push
+/// [`convolution`]: ./fn.convolution.html
+#[derive(Clone)]
+pub struct OnlineConvolution<M: Modulus> {
+    a: Vec<StaticModInt<M>>,
+    b: Vec<StaticModInt<M>>,
+    c: Vec<StaticModInt<M>>,
+    // butterfly of a[2^k..2^(k+1)] and b[2^k..2^(k+1)], zero-padded to 2^(k+1)
+    fa: Vec<Vec<StaticModInt<M>>>,
+    fb: Vec<Vec<StaticModInt<M>>>,
+}
+
+impl<M: Modulus> Default for OnlineConvolution<M> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<M: Modulus> OnlineConvolution<M> {
+    /// Creates a new `OnlineConvolution` with no terms.
+    pub fn new() -> Self {
+        Self {
+            a: vec![],
+            b: vec![],
+            c: vec![],
+            fa: vec![],
+            fb: vec![],
+        }
+    }
+
+    /// Returns the number of terms pushed so far.
+    pub fn len(&self) -> usize {
+        self.a.len()
+    }
+
+    /// Returns `true` if no terms have been pushed.
+    pub fn is_empty(&self) -> bool {
+        self.a.is_empty()
+    }
+
+    /// Appends $a_i, b_i$ and returns $c_i$, where $i$ is [`len`] before the call.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(\log^2 n)$ amortized
+    ///
+    /// [`len`]: #method.len
+    #[allow(clippy::many_single_char_names)]
+    pub fn push(&mut self, a: StaticModInt<M>, b: StaticModInt<M>) -> StaticModInt<M> {
+        // We split the pairs (x, y) with x, y >= 1 into the squares
+        //   [j * 2^k, (j + 1) * 2^k) x [2^k, 2^(k+1)) (j >= 1) and their transpositions (j >= 2).
+        // Every input of a square is at most (j + 1) * 2^k - 1 and every output is at least (j + 1) * 2^k,
+        // so each square is processed at the moment its last input arrives.
+        const NAIVE_THRESHOLD: usize = 32;
+
+        let i = self.a.len();
+        self.a.push(a);
+        self.b.push(b);
+        if self.c.len() < 2 * (i + 1) {
+            self.c.resize(2 * (i + 1), StaticModInt::raw(0));
+        }
+        self.c[i] += self.a[i] * self.b[0];
+        if i > 0 {
+            self.c[i] += self.a[0] * self.b[i];
+        }
+
+        let n = i + 1;
+        let mut k = 0;
+        while n % (1 << k) == 0 && 2 << k <= n {
+            let p = 1 << k;
+            let s = n - p;
+            let symmetric = s > p;
+            if p <= NAIVE_THRESHOLD {
+                for x in s..n {
+                    for y in p..2 * p {
+                        self.c[x + y] += self.a[x] * self.b[y];
+                        if symmetric {
+                            self.c[x + y] += self.b[x] * self.a[y];
+                        }
+                    }
+                }
+            } else {
+                if self.fa.len() <= k {
+                    self.fa.resize(k + 1, vec![]);
+                    self.fb.resize(k + 1, vec![]);
+                }
+                if self.fa[k].is_empty() {
+                    let mut fa = self.a[p..2 * p].to_vec();
+                    fa.resize(2 * p, StaticModInt::raw(0));
+                    butterfly(&mut fa);
+                    let mut fb = self.b[p..2 * p].to_vec();
+                    fb.resize(2 * p, StaticModInt::raw(0));
+                    butterfly(&mut fb);
+                    self.fa[k] = fa;
+                    self.fb[k] = fb;
+                }
+                let mut x = self.a[s..n].to_vec();
+                x.resize(2 * p, StaticModInt::raw(0));
+                butterfly(&mut x);
+                for (x, fb) in x.iter_mut().zip(&self.fb[k]) {
+                    *x *= fb;
+                }
+                if symmetric {
+                    let mut y = self.b[s..n].to_vec();
+                    y.resize(2 * p, StaticModInt::raw(0));
+                    butterfly(&mut y);
+                    for ((x, y), fa) in x.iter_mut().zip(y).zip(&self.fa[k]) {
+                        *x += y * fa;
+                    }
+                }
+                butterfly_inv(&mut x);
+                let iz = StaticModInt::new(2 * p).inv();
+                for (c, x) in self.c[n..].iter_mut().zip(&x[..2 * p - 1]) {
+                    *c += x * iz;
+                }
+            }
+            k += 1;
+        }
+        self.c[i]
+    }
+}
+
 #[allow(clippy::many_single_char_names)]
 fn butterfly<M: Modulus>(a: &mut [StaticModInt<M>]) {
     let n = a.len();
@@ -579,6 +1067,167 @@ mod tests {
         assert_eq!(conv_naive(&a, &b), super::convolution(&a, &b));
     }
 
+    #[test]
+    fn online() {
+        let mut rng = rand::thread_rng();
+        let mut gen_values = |n| gen_values::<Mod998244353>(&mut rng, n);
+        for &n in &[1, 2, 3, 31, 64, 65, 127, 128, 500] {
+            let (a, b) = (gen_values(n), gen_values(n));
+            let expected = conv_naive(&a, &b);
+            let mut conv = super::OnlineConvolution::new();
+            for i in 0..n {
+                assert_eq!(conv.push(a[i], b[i]), expected[i]);
+            }
+            assert_eq!(conv.len(), n);
+        }
+    }
+
+    #[test]
+    fn online_self_referential() {
+        type Mint = StaticModInt<Mod998244353>;
+
+        // f_0 = 1, f_n = \sum_{i < n} f_i g_{n - i}
+        const N: usize = 300;
+        let mut rng = rand::thread_rng();
+        let g = gen_values::<Mod998244353>(&mut rng, N + 1);
+        let mut naive = vec![Mint::new(1)];
+        for n in 1..=N {
+            naive.push((0..n).map(|i| naive[i] * g[n - i]).sum());
+        }
+        let mut conv = super::OnlineConvolution::new();
+        let mut f = vec![Mint::new(1)];
+        for n in 1..=N {
+            let next = conv.push(f[n - 1], g[n]);
+            f.push(next);
+        }
+        assert_eq!(f, naive);
+    }
+
+    #[test]
+    fn conv_2d() {
+        let mut rng = rand::thread_rng();
+        for &(ha, wa, hb, wb) in &[(0, 0, 1, 1), (1, 1, 1, 1), (3, 4, 5, 2), (20, 30, 10, 40)] {
+            let mut gen_grid = |h, w| -> Vec<_> {
+                (0..h)
+                    .map(|_| gen_values::<Mod998244353>(&mut rng, w))
+                    .collect()
+            };
+            let (a, b) = (gen_grid(ha, wa), gen_grid(hb, wb));
+            let c = super::convolution_2d(&a, &b);
+            if ha == 0 {
+                assert!(c.is_empty());
+                continue;
+            }
+            let mut expected = vec![vec![StaticModInt::raw(0); wa + wb - 1]; ha + hb - 1];
+            for (i, j, k, l) in (0..ha).flat_map(|i| {
+                (0..wa).flat_map(move |j| {
+                    (0..hb).flat_map(move |k| (0..wb).map(move |l| (i, j, k, l)))
+                })
+            }) {
+                expected[i + k][j + l] += a[i][j] * b[k][l];
+            }
+            assert_eq!(c, expected);
+        }
+    }
+
+    #[test]
+    fn conv_nd() {
+        let mut rng = rand::thread_rng();
+        for (a_shape, b_shape, shape) in vec![
+            (vec![3], vec![4], None),
+            (vec![3], vec![4], Some(vec![2])),
+            (vec![3], vec![4], Some(vec![10])),
+            (vec![2, 3, 4], vec![3, 1, 2], None),
+            (vec![2, 3, 4], vec![3, 1, 2], Some(vec![3, 2, 1])),
+            (vec![5, 6, 7], vec![6, 5, 4], Some(vec![8, 8, 8])),
+            (vec![], vec![], None),
+        ] {
+            let (a, b) = (
+                gen_values::<Mod998244353>(&mut rng, a_shape.iter().product()),
+                gen_values::<Mod998244353>(&mut rng, b_shape.iter().product()),
+            );
+            let out_shape = shape.clone().unwrap_or_else(|| {
+                a_shape
+                    .iter()
+                    .zip(&b_shape)
+                    .map(|(a, b)| a + b - 1)
+                    .collect()
+            });
+            assert_eq!(
+                super::convolution_nd(&a, &a_shape, &b, &b_shape, shape.as_deref()),
+                conv_nd_naive(&a, &a_shape, &b, &b_shape, &out_shape, usize::MAX),
+            );
+        }
+    }
+
+    #[test]
+    fn multivariate() {
+        let mut rng = rand::thread_rng();
+        for shape in vec![
+            vec![],
+            vec![0],
+            vec![1],
+            vec![10],
+            vec![3, 5],
+            vec![1, 7, 1],
+            vec![2, 3, 2, 3],
+            vec![4, 4, 4],
+        ] {
+            let n = shape.iter().product();
+            let (a, b) = (
+                gen_values::<Mod998244353>(&mut rng, n),
+                gen_values::<Mod998244353>(&mut rng, n),
+            );
+            assert_eq!(
+                super::multivariate_convolution(&a, &b, &shape),
+                conv_nd_naive(&a, &shape, &b, &shape, &shape, usize::MAX),
+            );
+            for degree in 0..5 {
+                assert_eq!(
+                    super::multivariate_convolution_total_degree(&a, &b, &shape, degree),
+                    conv_nd_naive(&a, &shape, &b, &shape, &shape, degree),
+                );
+            }
+        }
+    }
+
+    fn conv_nd_naive<M: Modulus>(
+        a: &[StaticModInt<M>],
+        a_shape: &[usize],
+        b: &[StaticModInt<M>],
+        b_shape: &[usize],
+        shape: &[usize],
+        degree: usize,
+    ) -> Vec<StaticModInt<M>> {
+        fn unflatten(mut i: usize, shape: &[usize]) -> Vec<usize> {
+            let mut ret = vec![0; shape.len()];
+            for d in (0..shape.len()).rev() {
+                ret[d] = i % shape[d];
+                i /= shape[d];
+            }
+            ret
+        }
+
+        let mut c = vec![StaticModInt::raw(0); shape.iter().product()];
+        for (i, &x) in a.iter().enumerate() {
+            for (j, &y) in b.iter().enumerate() {
+                let gamma = unflatten(i, a_shape)
+                    .into_iter()
+                    .zip(unflatten(j, b_shape))
+                    .map(|(i, j)| i + j)
+                    .collect::<Vec<_>>();
+                if gamma.iter().zip(shape).any(|(g, s)| g >= s)
+                    || gamma.iter().sum::<usize>() > degree
+                {
+                    continue;
+                }
+                let k = gamma.iter().zip(shape).fold(0, |k, (g, s)| k * s + g);
+                c[k] += x * y;
+            }
+        }
+        c
+    }
+
     #[allow(clippy::many_single_char_names)]
     fn conv_naive<M: Modulus>(
         a: &[StaticModInt<M>],
@@ -0,0 +1,125 @@
+//! Dirichlet convolution of arithmetic functions.
+
+use crate::modint::ModIntBase;
+
+/// Returns the Dirichlet convolution $c_k = \sum_{ij = k} a_i b_j$ for $k = 1, 2, \ldots, n - 1$, where $n = \min(|a|, |b|)$.
+///
+/// The arrays are 1-indexed: $a_0$ and $b_0$ are ignored, and $c_0$ is $0$.
+///
+/// # Complexity
+///
+/// - $O(n \log n)$
+///
+/// # Example
+///
+/// ```
+/// use ac_library::{dirichlet::dirichlet_convolution, ModInt998244353 as Mint};
+///
+/// // 1 * 1 = the number of divisors
+/// let one = vec![Mint::new(1); 7];
+/// let d = dirichlet_convolution(&one, &one);
+/// assert_eq!(d, [0, 1, 2, 2, 3, 2, 4].iter().map(|&x| Mint::new(x)).collect::<Vec<_>>());
+/// ```
+pub fn dirichlet_convolution<Z: ModIntBase>(a: &[Z], b: &[Z]) -> Vec<Z> {
+    let n = a.len().min(b.len());
+    let mut c = vec![Z::new(0); n];
+    for (i, &ai) in a.iter().enumerate().take(n).skip(1) {
+        for (j, &bj) in b.iter().enumerate().take((n - 1) / i + 1).skip(1) {
+            c[i * j] += ai * bj;
+        }
+    }
+    c
+}
+
+/// Returns the Dirichlet inverse $b$ of $a$, i.e. $b$ such that $\sum_{ij = k} a_i b_j = [k = 1]$ for $k = 1, 2, \ldots, |a| - 1$.
+///
+/// The arrays are 1-indexed: $a_0$ is ignored, and $b_0$ is $0$.
+///
+/// # Constraints
+///
+/// - $a_1$ is invertible, if $|a| \geq 2$
+///
+/// # Panics
+///
+/// Panics if the above constraints are not satisfied.
+///
+/// # Complexity
+///
+/// - $O(n \log n)$
+///
+/// # Example
+///
+/// ```
+/// use ac_library::{dirichlet::dirichlet_inverse, ModInt998244353 as Mint};
+///
+/// // the inverse of 1 is the Möbius function
+/// let mu = dirichlet_inverse(&[Mint::new(1); 7]);
+/// assert_eq!(mu, [0, 1, -1, -1, 0, -1, 1].iter().map(|&x| Mint::new(x)).collect::<Vec<_>>());
+/// ```
+pub fn dirichlet_inverse<Z: ModIntBase>(a: &[Z]) -> Vec<Z> {
+    let n = a.len();
+    let mut b = vec![Z::new(0); n];
+    if n < 2 {
+        return b;
+    }
+    let inv = a[1].inv();
+    b[1] = inv;
+    for i in 1..n {
+        // b[i] is fixed; b[i] holds -\sum_{jd = i, j > 1} a[j] b[d] before this
+        if i > 1 {
+            b[i] = -b[i] * inv;
+        }
+        let bi = b[i];
+        for (j, &aj) in a.iter().enumerate().take((n - 1) / i + 1).skip(2) {
+            b[i * j] += aj * bi;
+        }
+    }
+    b
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::Sieve;
+
+    #[test]
+    fn test_dirichlet() {
+        type Mint = crate::ModInt998244353;
+
+        let n = 2000;
+        let sieve = Sieve::new(n - 1);
+        let one = vec![Mint::new(1); n];
+        let id = (0..n).map(Mint::new).collect::<Vec<_>>();
+        let mu = sieve
+            .mobius_table()
+            .iter()
+            .map(|&x| Mint::new(x))
+            .collect::<Vec<_>>();
+        let phi = sieve
+            .totient_table()
+            .iter()
+            .map(|&x| Mint::new(x))
+            .collect::<Vec<_>>();
+        let d = sieve
+            .divisor_count_table()
+            .iter()
+            .map(|&x| Mint::new(x))
+            .collect::<Vec<_>>();
+        let mut mu0 = mu.clone();
+        mu0[0] = Mint::new(0);
+        assert_eq!(dirichlet_inverse(&one), mu0);
+        assert_eq!(dirichlet_convolution(&one, &one)[1..], d[1..]);
+        assert_eq!(dirichlet_convolution(&mu, &id)[1..], phi[1..]);
+        assert_eq!(dirichlet_convolution(&phi, &one)[1..], id[1..]);
+        assert_eq!(dirichlet_convolution(&phi, &one[..10]).len(), 10);
+
+        let a = (0..n)
+            .map(|i| Mint::new(i * i + 3 * i + 5))
+            .collect::<Vec<_>>();
+        let mut unit = vec![Mint::new(0); n];
+        unit[1] = Mint::new(1);
+        assert_eq!(dirichlet_convolution(&a, &dirichlet_inverse(&a)), unit);
+        assert!(dirichlet_inverse::<Mint>(&[]).is_empty());
+        assert_eq!(dirichlet_inverse(&a[..1]), [Mint::new(0)]);
+    }
+}
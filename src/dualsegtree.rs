@@ -0,0 +1,237 @@
+//! A segment tree for range updates and point reads.
+
+use crate::internal_bit::ceil_pow2;
+use crate::segtree::Monoid;
+use std::iter::FromIterator;
+use std::ops::{Bound, RangeBounds};
+
+/// A segment tree that applies an element of a monoid to a range and reads a single element.
+///
+/// Applying $f$ to the $i$-th element $x$ replaces it with $x \cdot f$.
+/// For a monoid of maps like [`Affine`], where $x \cdot f$ is the map that applies $x$ first and then $f$, the $i$-th element is the composition of the maps applied to it in order.
+///
+/// Unlike [`LazySegtree`], it needs no monoid for range products.
+/// The monoid does not have to be commutative.
+///
+/// # Example
+///
+/// ```
+/// use ac_library::{Affine, DualSegtree, ModInt998244353 as Mint};
+///
+/// let mut segtree = DualSegtree::<Affine<Mint>>::new(5);
+/// segtree.apply_range(1..4, (Mint::new(2), Mint::new(1)));
+/// segtree.apply_range(2.., (Mint::new(3), Mint::new(0)));
+/// // x -> 3(2x + 1)
+/// assert_eq!(segtree.get(3), (Mint::new(6), Mint::new(3)));
+/// assert_eq!(segtree.get(4), (Mint::new(3), Mint::new(0)));
+/// assert_eq!(segtree.get(0), (Mint::new(1), Mint::new(0)));
+/// ```
+///
+/// [`Affine`]: ../segtree/struct.Affine.html
+/// [`LazySegtree`]: ../lazysegtree/struct.LazySegtree.html
+#[derive(Clone)]
+pub struct DualSegtree<F>
+where
+    F: Monoid,
+{
+    n: usize,
+    size: usize,
+    log: usize,
+    // `d[size..]` are the elements and `d[..size]` are the pending maps.
+    d: Vec<F::S>,
+}
+
+impl<F: Monoid> Default for DualSegtree<F> {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+impl<F: Monoid> DualSegtree<F> {
+    pub fn new(n: usize) -> Self {
+        vec![F::identity(); n].into()
+    }
+}
+impl<F: Monoid> From<Vec<F::S>> for DualSegtree<F> {
+    fn from(v: Vec<F::S>) -> Self {
+        let n = v.len();
+        let log = ceil_pow2(n as u32) as usize;
+        let size = 1 << log;
+        let mut d = vec![F::identity(); 2 * size];
+        d[size..][..n].clone_from_slice(&v);
+        DualSegtree { n, size, log, d }
+    }
+}
+impl<F: Monoid> FromIterator<F::S> for DualSegtree<F> {
+    fn from_iter<T: IntoIterator<Item = F::S>>(iter: T) -> Self {
+        iter.into_iter().collect::<Vec<_>>().into()
+    }
+}
+
+impl<F: Monoid> DualSegtree<F> {
+    /// Sets the $p$-th element to $x$.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(\log n)$
+    pub fn set(&mut self, mut p: usize, x: F::S) {
+        assert!(p < self.n);
+        p += self.size;
+        for i in (1..=self.log).rev() {
+            self.push(p >> i);
+        }
+        self.d[p] = x;
+    }
+
+    /// Returns the $p$-th element.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(\log n)$
+    pub fn get(&self, mut p: usize) -> F::S {
+        assert!(p < self.n);
+        p += self.size;
+        let mut x = self.d[p].clone();
+        while p > 1 {
+            p >>= 1;
+            x = F::binary_operation(&x, &self.d[p]);
+        }
+        x
+    }
+
+    /// Applies $f$ to the $p$-th element.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(\log n)$
+    pub fn apply(&mut self, mut p: usize, f: F::S) {
+        assert!(p < self.n);
+        p += self.size;
+        for i in (1..=self.log).rev() {
+            self.push(p >> i);
+        }
+        self.d[p] = F::binary_operation(&self.d[p], &f);
+    }
+
+    /// Applies $f$ to the elements in `range`.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(\log n)$
+    pub fn apply_range<R>(&mut self, range: R, f: F::S)
+    where
+        R: RangeBounds<usize>,
+    {
+        let mut r = match range.end_bound() {
+            Bound::Included(r) => r + 1,
+            Bound::Excluded(r) => *r,
+            Bound::Unbounded => self.n,
+        };
+        let mut l = match range.start_bound() {
+            Bound::Included(l) => *l,
+            Bound::Excluded(l) => l + 1,
+            Bound::Unbounded => 0,
+        };
+
+        assert!(l <= r && r <= self.n);
+        if l == r {
+            return;
+        }
+
+        l += self.size;
+        r += self.size;
+
+        for i in (1..=self.log).rev() {
+            if ((l >> i) << i) != l {
+                self.push(l >> i);
+            }
+            if ((r >> i) << i) != r {
+                self.push((r - 1) >> i);
+            }
+        }
+
+        while l < r {
+            if l & 1 != 0 {
+                self.all_apply(l, &f);
+                l += 1;
+            }
+            if r & 1 != 0 {
+                r -= 1;
+                self.all_apply(r, &f);
+            }
+            l >>= 1;
+            r >>= 1;
+        }
+    }
+
+    fn all_apply(&mut self, k: usize, f: &F::S) {
+        self.d[k] = F::binary_operation(&self.d[k], f);
+    }
+
+    fn push(&mut self, k: usize) {
+        let f = std::mem::replace(&mut self.d[k], F::identity());
+        self.all_apply(2 * k, &f);
+        self.all_apply(2 * k + 1, &f);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modint::ModInt998244353;
+    use crate::segtree::{Additive, Affine, Max};
+    use rand::Rng;
+    use std::ops::Bound::*;
+
+    type Z = ModInt998244353;
+
+    #[test]
+    fn test_dual_segtree_affine() {
+        let mut rng = rand::thread_rng();
+        for n in 0..20 {
+            let mut naive = vec![(Z::new(1), Z::new(0)); n];
+            let mut segtree = DualSegtree::<Affine<Z>>::new(n);
+            for _ in 0..200 {
+                let l = rng.gen_range(0, n + 1);
+                let r = rng.gen_range(l, n + 1);
+                let f = (Z::new(rng.gen_range(0, 10)), Z::new(rng.gen_range(0, 10)));
+                match rng.gen_range(0, 3) {
+                    0 => {
+                        segtree.apply_range(l..r, f);
+                        for x in &mut naive[l..r] {
+                            *x = (f.0 * x.0, f.0 * x.1 + f.1);
+                        }
+                    }
+                    1 if l < r => {
+                        segtree.set(l, f);
+                        naive[l] = f;
+                    }
+                    _ if l < r => {
+                        segtree.apply(l, f);
+                        naive[l] = (f.0 * naive[l].0, f.0 * naive[l].1 + f.1);
+                    }
+                    _ => {}
+                }
+                for (i, &x) in naive.iter().enumerate() {
+                    assert_eq!(segtree.get(i), x);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_dual_segtree_bounds() {
+        let mut segtree: DualSegtree<Additive<i32>> = vec![0, 1, 2, 3, 4].into();
+        segtree.apply_range(.., 10);
+        segtree.apply_range(..2, 100);
+        segtree.apply_range(3.., 1000);
+        segtree.apply_range(1..=3, 10000);
+        segtree.apply_range((Excluded(1), Included(2)), 100000);
+        let v = (0..5).map(|i| segtree.get(i)).collect::<Vec<_>>();
+        assert_eq!(v, [110, 10111, 110012, 11013, 1014]);
+
+        let mut segtree: DualSegtree<Max<i32>> = (0..5).collect();
+        segtree.apply_range(1..4, 2);
+        let v = (0..5).map(|i| segtree.get(i)).collect::<Vec<_>>();
+        assert_eq!(v, [0, 2, 2, 3, 4]);
+    }
+}
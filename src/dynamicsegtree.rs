@@ -0,0 +1,444 @@
+//! A segment tree over a huge index range, whose nodes are created on demand.
+
+use crate::internal_type_traits::Integral;
+use crate::segtree::Monoid;
+use std::ops::{Bound, RangeBounds};
+
+const NIL: usize = !0;
+
+/// A segment tree over the index range $[\mathrm{lo}, \mathrm{hi})$, which allocates only the nodes on the paths to the updated points.
+///
+/// The elements are initially `M::identity()`.
+/// The nodes are stored in an arena (a `Vec`), so no per-node allocation occurs.
+///
+/// # Example
+///
+/// ```
+/// use ac_library::{Additive, DynamicSegtree};
+///
+/// let mut segtree = DynamicSegtree::<Additive<i64>>::new(-1_000_000_000_000_000_000, 1_000_000_000_000_000_000);
+/// segtree.set(-5, 3);
+/// segtree.set(999_999_999_999_999_999, 4);
+/// assert_eq!(segtree.prod(-10..10), 3);
+/// assert_eq!(segtree.prod(..), 7);
+/// assert_eq!(segtree.max_right(-10, |&x| x < 7), 999_999_999_999_999_999);
+/// ```
+#[derive(Clone)]
+pub struct DynamicSegtree<M, I = i64>
+where
+    M: Monoid,
+    I: Integral,
+{
+    lo: I,
+    hi: I,
+    d: Vec<M::S>,
+    children: Vec<[usize; 2]>,
+}
+
+impl<M: Monoid, I: Integral> DynamicSegtree<M, I> {
+    /// Creates a segment tree over $[\mathrm{lo}, \mathrm{hi})$.
+    ///
+    /// # Constraints
+    ///
+    /// - $\mathrm{lo} < \mathrm{hi}$
+    ///
+    /// # Complexity
+    ///
+    /// - $O(1)$
+    pub fn new(lo: I, hi: I) -> Self {
+        Self::with_capacity(lo, hi, 0)
+    }
+
+    /// Creates a segment tree over $[\mathrm{lo}, \mathrm{hi})$, reserving the arena for `capacity` nodes.
+    ///
+    /// Each `set` creates at most $\lceil \log_2 (\mathrm{hi} - \mathrm{lo}) \rceil + 1$ nodes.
+    pub fn with_capacity(lo: I, hi: I, capacity: usize) -> Self {
+        assert!(lo < hi);
+        let mut ret = DynamicSegtree {
+            lo,
+            hi,
+            d: Vec::with_capacity(capacity),
+            children: Vec::with_capacity(capacity),
+        };
+        ret.new_node();
+        ret
+    }
+
+    /// Sets the $p$-th element to $x$.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(\log (\mathrm{hi} - \mathrm{lo}))$
+    pub fn set(&mut self, p: I, x: M::S) {
+        assert!(self.lo <= p && p < self.hi);
+        self.set_rec(0, self.lo, self.hi, p, x);
+    }
+
+    /// Returns the $p$-th element.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(\log (\mathrm{hi} - \mathrm{lo}))$
+    pub fn get(&self, p: I) -> M::S {
+        assert!(self.lo <= p && p < self.hi);
+        let (mut node, mut l, mut r) = (0, self.lo, self.hi);
+        while l + I::one() < r {
+            let m = mid(l, r);
+            let dir = (p >= m) as usize;
+            node = self.children[node][dir];
+            if node == NIL {
+                return M::identity();
+            }
+            if dir == 0 {
+                r = m;
+            } else {
+                l = m;
+            }
+        }
+        self.d[node].clone()
+    }
+
+    /// Returns the product of the elements in `range`.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(\log (\mathrm{hi} - \mathrm{lo}))$
+    pub fn prod<R>(&self, range: R) -> M::S
+    where
+        R: RangeBounds<I>,
+    {
+        let r = match range.end_bound() {
+            Bound::Included(r) => {
+                // checked here because `*r + 1` would overflow for `..=I::MAX`
+                assert!(*r < self.hi, "the range must end at most at hi");
+                *r + I::one()
+            }
+            Bound::Excluded(r) => *r,
+            Bound::Unbounded => self.hi,
+        };
+        let l = match range.start_bound() {
+            Bound::Included(l) => *l,
+            Bound::Excluded(l) => {
+                assert!(*l < self.hi, "the range must start at most at hi");
+                *l + I::one()
+            }
+            Bound::Unbounded => self.lo,
+        };
+        assert!(self.lo <= l && l <= r && r <= self.hi);
+        self.prod_rec(0, self.lo, self.hi, l, r)
+    }
+
+    /// Returns the product of all the elements.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(1)$
+    pub fn all_prod(&self) -> M::S {
+        self.d[0].clone()
+    }
+
+    /// Returns an index $r$ such that `f(&prod(l..r))` is `true` and `f(&prod(l..r + 1))` is `false`, or $\mathrm{hi}$, in the same manner as [`Segtree::max_right`].
+    ///
+    /// # Constraints
+    ///
+    /// - $\mathrm{lo} \leq l \leq \mathrm{hi}$
+    /// - `f(&M::identity())` is `true`
+    /// - `f` is monotone
+    ///
+    /// # Complexity
+    ///
+    /// - $O(\log (\mathrm{hi} - \mathrm{lo}))$
+    ///
+    /// [`Segtree::max_right`]: ../segtree/struct.Segtree.html#method.max_right
+    pub fn max_right<F>(&self, l: I, f: F) -> I
+    where
+        F: Fn(&M::S) -> bool,
+    {
+        assert!(self.lo <= l && l <= self.hi);
+        assert!(f(&M::identity()));
+        let mut sm = M::identity();
+        self.max_right_rec(0, self.lo, self.hi, l, &f, &mut sm)
+            .unwrap_or(self.hi)
+    }
+
+    /// Returns an index $l$ such that `f(&prod(l..r))` is `true` and `f(&prod(l - 1..r))` is `false`, or $\mathrm{lo}$, in the same manner as [`Segtree::min_left`].
+    ///
+    /// # Constraints
+    ///
+    /// - $\mathrm{lo} \leq r \leq \mathrm{hi}$
+    /// - `f(&M::identity())` is `true`
+    /// - `f` is monotone
+    ///
+    /// # Complexity
+    ///
+    /// - $O(\log (\mathrm{hi} - \mathrm{lo}))$
+    ///
+    /// [`Segtree::min_left`]: ../segtree/struct.Segtree.html#method.min_left
+    pub fn min_left<F>(&self, r: I, f: F) -> I
+    where
+        F: Fn(&M::S) -> bool,
+    {
+        assert!(self.lo <= r && r <= self.hi);
+        assert!(f(&M::identity()));
+        let mut sm = M::identity();
+        self.min_left_rec(0, self.lo, self.hi, r, &f, &mut sm)
+            .unwrap_or(self.lo)
+    }
+
+    fn new_node(&mut self) -> usize {
+        self.d.push(M::identity());
+        self.children.push([NIL; 2]);
+        self.d.len() - 1
+    }
+
+    fn update(&mut self, k: usize) {
+        let [a, b] = self.children[k];
+        self.d[k] = match (a != NIL, b != NIL) {
+            (true, true) => M::binary_operation(&self.d[a], &self.d[b]),
+            (true, false) => self.d[a].clone(),
+            (false, true) => self.d[b].clone(),
+            (false, false) => M::identity(),
+        };
+    }
+
+    fn set_rec(&mut self, node: usize, l: I, r: I, p: I, x: M::S) {
+        if l + I::one() == r {
+            self.d[node] = x;
+            return;
+        }
+        let m = mid(l, r);
+        let dir = (p >= m) as usize;
+        if self.children[node][dir] == NIL {
+            let child = self.new_node();
+            self.children[node][dir] = child;
+        }
+        let child = self.children[node][dir];
+        if dir == 0 {
+            self.set_rec(child, l, m, p, x);
+        } else {
+            self.set_rec(child, m, r, p, x);
+        }
+        self.update(node);
+    }
+
+    fn prod_rec(&self, node: usize, nl: I, nr: I, l: I, r: I) -> M::S {
+        if node == NIL || nr <= l || r <= nl {
+            return M::identity();
+        }
+        if l <= nl && nr <= r {
+            return self.d[node].clone();
+        }
+        let m = mid(nl, nr);
+        let [a, b] = self.children[node];
+        M::binary_operation(
+            &self.prod_rec(a, nl, m, l, r),
+            &self.prod_rec(b, m, nr, l, r),
+        )
+    }
+
+    /// Returns the answer if it is in $[\max(l, nl), nr)$; otherwise multiplies the product of the range to `sm`.
+    fn max_right_rec<F>(&self, node: usize, nl: I, nr: I, l: I, f: &F, sm: &mut M::S) -> Option<I>
+    where
+        F: Fn(&M::S) -> bool,
+    {
+        // an absent node is the identity, which never makes `f` false
+        if node == NIL || nr <= l {
+            return None;
+        }
+        if l <= nl {
+            let res = M::binary_operation(sm, &self.d[node]);
+            if f(&res) {
+                *sm = res;
+                return None;
+            }
+            if nl + I::one() == nr {
+                return Some(nl);
+            }
+        }
+        let m = mid(nl, nr);
+        let [a, b] = self.children[node];
+        self.max_right_rec(a, nl, m, l, f, sm)
+            .or_else(|| self.max_right_rec(b, m, nr, l, f, sm))
+    }
+
+    /// Returns the answer if it is in $(nl, \min(r, nr)]$; otherwise multiplies the product of the range to `sm` from the left.
+    fn min_left_rec<F>(&self, node: usize, nl: I, nr: I, r: I, f: &F, sm: &mut M::S) -> Option<I>
+    where
+        F: Fn(&M::S) -> bool,
+    {
+        if node == NIL || r <= nl {
+            return None;
+        }
+        if nr <= r {
+            let res = M::binary_operation(&self.d[node], sm);
+            if f(&res) {
+                *sm = res;
+                return None;
+            }
+            if nl + I::one() == nr {
+                return Some(nr);
+            }
+        }
+        let m = mid(nl, nr);
+        let [a, b] = self.children[node];
+        self.min_left_rec(b, m, nr, r, f, sm)
+            .or_else(|| self.min_left_rec(a, nl, m, r, f, sm))
+    }
+}
+
+/// Returns $m$ such that $l < m < r$ and $m \approx (l + r) / 2$ without overflow, where $l + 2 \leq r$.
+fn mid<I: Integral>(l: I, r: I) -> I {
+    let two = I::one() + I::one();
+    l / two + r / two + (l % two + r % two) / two
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::segtree::{Additive, Max, Min};
+    use rand::{rngs::ThreadRng, Rng};
+    use std::ops::Bound::{Excluded, Unbounded};
+
+    #[test]
+    fn test_mid() {
+        for l in -10i64..10 {
+            for r in l + 2..10 {
+                let m = mid(l, r);
+                assert!(l < m && m < r);
+                assert!((m - l - (r - m)).abs() <= 2);
+            }
+        }
+        assert_eq!(mid(i64::MIN, i64::MAX), -1);
+        assert_eq!(mid(i64::MAX - 1, i64::MAX), i64::MAX - 1);
+        assert_eq!(mid(u64::MAX - 3, u64::MAX), u64::MAX - 2);
+    }
+
+    /// `f` in `max_right` and `min_left` is `x <= t` if `le`, otherwise `x >= t`.
+    fn check<M>(rng: &mut ThreadRng, lo: i64, hi: i64, queries: usize, le: bool)
+    where
+        M: Monoid<S = i64>,
+    {
+        let mut segtree = DynamicSegtree::<M>::new(lo, hi);
+        let mut naive = vec![M::identity(); (hi - lo) as usize];
+        let prod = |v: &[i64]| {
+            v.iter()
+                .fold(M::identity(), |a, b| M::binary_operation(&a, b))
+        };
+        for _ in 0..queries {
+            match rng.gen_range(0, 5) {
+                0 => {
+                    let p = rng.gen_range(lo, hi);
+                    let x = rng.gen_range(0, 100);
+                    segtree.set(p, x);
+                    naive[(p - lo) as usize] = x;
+                }
+                1 => {
+                    let p = rng.gen_range(lo, hi);
+                    assert_eq!(segtree.get(p), naive[(p - lo) as usize]);
+                }
+                2 => {
+                    let l = rng.gen_range(lo, hi + 1);
+                    let r = rng.gen_range(l, hi + 1);
+                    let v = &naive[(l - lo) as usize..(r - lo) as usize];
+                    assert_eq!(segtree.prod(l..r), prod(v));
+                    assert_eq!(segtree.prod(..r), prod(&naive[..(r - lo) as usize]));
+                    assert_eq!(segtree.prod(l..), prod(&naive[(l - lo) as usize..]));
+                    if l < r {
+                        assert_eq!(segtree.prod(l..=r - 1), prod(v));
+                    }
+                }
+                3 => {
+                    let l = rng.gen_range(lo, hi + 1);
+                    let t = rng.gen_range(-100, 300);
+                    let f = |&x: &i64| if le { x <= t } else { x >= t };
+                    if f(&M::identity()) {
+                        let expected = (l..=hi)
+                            .take_while(|&r| f(&prod(&naive[(l - lo) as usize..(r - lo) as usize])))
+                            .last()
+                            .unwrap();
+                        assert_eq!(segtree.max_right(l, f), expected);
+                    }
+                }
+                _ => {
+                    let r = rng.gen_range(lo, hi + 1);
+                    let t = rng.gen_range(-100, 300);
+                    let f = |&x: &i64| if le { x <= t } else { x >= t };
+                    if f(&M::identity()) {
+                        let expected = (lo..=r)
+                            .rev()
+                            .take_while(|&l| f(&prod(&naive[(l - lo) as usize..(r - lo) as usize])))
+                            .last()
+                            .unwrap();
+                        assert_eq!(segtree.min_left(r, f), expected);
+                    }
+                }
+            }
+            assert_eq!(segtree.all_prod(), prod(&naive));
+        }
+    }
+
+    #[test]
+    fn test_dynamic_segtree_random() {
+        let mut rng = rand::thread_rng();
+        for &(lo, hi) in &[(0, 1), (0, 2), (-3, 4), (-50, 50), (7, 100)] {
+            check::<Additive<i64>>(&mut rng, lo, hi, 500, true);
+            check::<Max<i64>>(&mut rng, lo, hi, 500, true);
+            check::<Min<i64>>(&mut rng, lo, hi, 500, false);
+        }
+    }
+
+    #[test]
+    fn test_dynamic_segtree_huge() {
+        let mut segtree = DynamicSegtree::<Additive<i64>>::new(i64::MIN, i64::MAX);
+        let points = [
+            i64::MIN,
+            -1_000_000_000_000,
+            -1,
+            0,
+            3,
+            1 << 60,
+            i64::MAX - 1,
+        ];
+        for (i, &p) in points.iter().enumerate() {
+            segtree.set(p, 1 << i);
+        }
+        for (i, &p) in points.iter().enumerate() {
+            assert_eq!(segtree.get(p), 1 << i);
+            assert_eq!(segtree.prod(..p), (1 << i) - 1);
+            assert_eq!(segtree.prod(p..), (1 << points.len()) - (1 << i));
+            assert_eq!(segtree.max_right(i64::MIN, |&x| x < 1 << i), p);
+            let expected = if i == 0 { i64::MIN } else { points[i - 1] + 1 };
+            assert_eq!(segtree.min_left(p, |&x| x < 1), expected);
+        }
+        assert_eq!(segtree.get(2), 0);
+        assert_eq!(segtree.prod(1..3), 0);
+        assert_eq!(segtree.max_right(0, |&x| x < 100), i64::MAX - 1);
+        assert_eq!(segtree.min_left(i64::MAX, |&x| x < 64), i64::MAX);
+        assert_eq!(segtree.min_left(i64::MAX, |&x| x < 100), 3 + 1);
+        assert!(segtree.d.len() <= points.len() * 64 + 1);
+
+        let mut segtree = DynamicSegtree::<Max<u64>, u64>::with_capacity(0, u64::MAX, 200);
+        segtree.set(u64::MAX - 1, 5);
+        segtree.set(0, 3);
+        assert_eq!(segtree.prod(1..), 5);
+        assert_eq!(segtree.max_right(0, |&x| x < 5), u64::MAX - 1);
+        assert_eq!(segtree.min_left(u64::MAX, |&x| x < 5), u64::MAX);
+        assert_eq!(segtree.min_left(u64::MAX - 1, |&x| x < 3), 1);
+        assert_eq!(segtree.prod(..=u64::MAX - 1), 5);
+        assert_eq!(segtree.prod((Excluded(u64::MAX - 1), Unbounded)), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "the range must end at most at hi")]
+    fn test_dynamic_segtree_included_max() {
+        let segtree = DynamicSegtree::<Additive<i64>>::new(i64::MIN, i64::MAX);
+        segtree.prod(..=i64::MAX);
+    }
+
+    #[test]
+    #[should_panic(expected = "the range must start at most at hi")]
+    fn test_dynamic_segtree_excluded_max() {
+        let segtree = DynamicSegtree::<Max<u64>, u64>::new(0, u64::MAX);
+        segtree.prod((Excluded(u64::MAX), Unbounded));
+    }
+}
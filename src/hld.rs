@@ -0,0 +1,503 @@
+//! Heavy-light decomposition for path and subtree queries on a tree.
+
+use crate::lazysegtree::{LazySegtree, MapMonoid};
+use crate::segtree::{Monoid, Reversed, Segtree};
+use std::ops::Range;
+
+/// Heavy-light decomposition of a rooted tree.
+///
+/// It assigns each vertex an index in $[0, n)$ such that
+///
+/// - every heavy path occupies a contiguous range, ordered from the top to the bottom,
+/// - every subtree occupies a contiguous range, starting at its root.
+///
+/// Then the path between two vertices is split into $O(\log n)$ ranges, and a [`Segtree`] or a [`LazySegtree`] over the indices answers path and subtree queries.
+///
+/// For queries on edges, store the value of an edge at the index of its deeper endpoint and use [`path_edges`].
+///
+/// # Example
+///
+/// ```
+/// use ac_library::{Additive, Hld, Segtree};
+///
+/// //     0
+/// //    / \
+/// //   1   2
+/// //  / \
+/// // 3   4
+/// let hld = Hld::new(5, &[(0, 1), (0, 2), (1, 3), (1, 4)], 0);
+/// assert_eq!(hld.lca(3, 4), 1);
+/// assert_eq!(hld.dist(3, 2), 3);
+///
+/// let weights = [1, 10, 100, 1000, 10000];
+/// let mut v = vec![0; 5];
+/// for (i, &w) in weights.iter().enumerate() {
+///     v[hld.index(i)] = w;
+/// }
+/// let segtree: Segtree<Additive<i32>> = v.into();
+/// assert_eq!(hld.prod_path_commutative(3, 2, &segtree), 1111);
+/// assert_eq!(hld.prod_subtree(1, &segtree), 11010);
+/// ```
+///
+/// [`Segtree`]: ../segtree/struct.Segtree.html
+/// [`LazySegtree`]: ../lazysegtree/struct.LazySegtree.html
+/// [`path_edges`]: #method.path_edges
+#[derive(Clone, Debug)]
+pub struct Hld {
+    parent: Vec<usize>,
+    depth: Vec<usize>,
+    head: Vec<usize>,
+    index: Vec<usize>,
+    vertex: Vec<usize>,
+    size: Vec<usize>,
+}
+
+impl Hld {
+    /// Decomposes the tree with $n$ vertices and `edges`, rooted at `root`.
+    ///
+    /// # Constraints
+    ///
+    /// - $1 \leq n$
+    /// - `edges` form a tree on the vertices $0, 1, \ldots, n - 1$
+    /// - $0 \leq \mathrm{root} < n$
+    ///
+    /// # Panics
+    ///
+    /// Panics if the above constraints are not satisfied.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(n)$
+    pub fn new(n: usize, edges: &[(usize, usize)], root: usize) -> Self {
+        assert!(root < n);
+        assert_eq!(edges.len() + 1, n);
+        let mut g = vec![vec![]; n];
+        for &(u, v) in edges {
+            g[u].push(v);
+            g[v].push(u);
+        }
+
+        let mut parent = vec![usize::MAX; n];
+        let mut depth = vec![0; n];
+        let mut order = Vec::with_capacity(n);
+        let mut stack = vec![root];
+        parent[root] = root;
+        while let Some(v) = stack.pop() {
+            order.push(v);
+            for &u in &g[v] {
+                if parent[u] == usize::MAX {
+                    parent[u] = v;
+                    depth[u] = depth[v] + 1;
+                    stack.push(u);
+                }
+            }
+        }
+        assert_eq!(order.len(), n, "the graph is not connected");
+
+        let mut size = vec![1; n];
+        let mut heavy = vec![usize::MAX; n];
+        for &v in order.iter().rev() {
+            if v != root {
+                size[parent[v]] += size[v];
+            }
+        }
+        for &v in &order {
+            for &u in &g[v] {
+                if u != parent[v] && (heavy[v] == usize::MAX || size[u] > size[heavy[v]]) {
+                    heavy[v] = u;
+                }
+            }
+        }
+
+        let mut head = vec![root; n];
+        let mut index = vec![0; n];
+        let mut vertex = Vec::with_capacity(n);
+        let mut stack = vec![root];
+        while let Some(v) = stack.pop() {
+            index[v] = vertex.len();
+            vertex.push(v);
+            for &u in &g[v] {
+                if u != parent[v] && u != heavy[v] {
+                    head[u] = u;
+                    stack.push(u);
+                }
+            }
+            if heavy[v] != usize::MAX {
+                head[heavy[v]] = head[v];
+                stack.push(heavy[v]);
+            }
+        }
+
+        Hld {
+            parent,
+            depth,
+            head,
+            index,
+            vertex,
+            size,
+        }
+    }
+
+    /// Returns the number of vertices.
+    pub fn len(&self) -> usize {
+        self.vertex.len()
+    }
+
+    /// Returns whether the tree has no vertices, which is always `false`.
+    pub fn is_empty(&self) -> bool {
+        self.vertex.is_empty()
+    }
+
+    /// Returns the index of the vertex $v$.
+    pub fn index(&self, v: usize) -> usize {
+        self.index[v]
+    }
+
+    /// Returns the vertex whose index is $i$.
+    pub fn vertex(&self, i: usize) -> usize {
+        self.vertex[i]
+    }
+
+    /// Returns the parent of $v$, or `None` if $v$ is the root.
+    pub fn parent(&self, v: usize) -> Option<usize> {
+        Some(self.parent[v]).filter(|&p| p != v)
+    }
+
+    /// Returns the depth of $v$, where the depth of the root is $0$.
+    pub fn depth(&self, v: usize) -> usize {
+        self.depth[v]
+    }
+
+    /// Returns the lowest common ancestor of $u$ and $v$.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(\log n)$
+    pub fn lca(&self, mut u: usize, mut v: usize) -> usize {
+        while self.head[u] != self.head[v] {
+            if self.depth[self.head[u]] < self.depth[self.head[v]] {
+                std::mem::swap(&mut u, &mut v);
+            }
+            u = self.parent[self.head[u]];
+        }
+        if self.depth[u] < self.depth[v] {
+            u
+        } else {
+            v
+        }
+    }
+
+    /// Returns the number of edges on the path between $u$ and $v$.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(\log n)$
+    pub fn dist(&self, u: usize, v: usize) -> usize {
+        self.depth[u] + self.depth[v] - 2 * self.depth[self.lca(u, v)]
+    }
+
+    /// Returns the range of the indices of the subtree of $v$.
+    ///
+    /// The first index is `index(v)`.
+    pub fn subtree(&self, v: usize) -> Range<usize> {
+        self.index[v]..self.index[v] + self.size[v]
+    }
+
+    /// Splits the path from $u$ to $v$ into ranges of indices, in the order of the path.
+    ///
+    /// Each element is `(range, reversed)`; if `reversed` is `true`, the path visits `range` in the decreasing order of the indices.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(\log n)$
+    pub fn path(&self, u: usize, v: usize) -> Vec<(Range<usize>, bool)> {
+        self.path_impl(u, v, false)
+    }
+
+    /// Same as [`path`], but without the index of the lowest common ancestor.
+    ///
+    /// If the value of each edge is at the index of its deeper endpoint, these ranges cover the edges on the path.
+    ///
+    /// [`path`]: #method.path
+    pub fn path_edges(&self, u: usize, v: usize) -> Vec<(Range<usize>, bool)> {
+        self.path_impl(u, v, true)
+    }
+
+    /// Returns the product of the values on the path from $u$ to $v$, in the order of the path.
+    ///
+    /// `segtree` and `reversed` must hold the same values, where the value of a vertex $w$ is at `index(w)`.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(\log^2 n)$
+    pub fn prod_path<M: Monoid>(
+        &self,
+        u: usize,
+        v: usize,
+        segtree: &Segtree<M>,
+        reversed: &Segtree<Reversed<M>>,
+    ) -> M::S {
+        self.path(u, v)
+            .into_iter()
+            .fold(M::identity(), |acc, (range, rev)| {
+                let x = if rev {
+                    reversed.prod(range)
+                } else {
+                    segtree.prod(range)
+                };
+                M::binary_operation(&acc, &x)
+            })
+    }
+
+    /// Returns the product of the values on the path between $u$ and $v$ for a commutative monoid.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(\log^2 n)$
+    pub fn prod_path_commutative<M: Monoid>(
+        &self,
+        u: usize,
+        v: usize,
+        segtree: &Segtree<M>,
+    ) -> M::S {
+        self.path(u, v)
+            .into_iter()
+            .fold(M::identity(), |acc, (range, _)| {
+                M::binary_operation(&acc, &segtree.prod(range))
+            })
+    }
+
+    /// Returns the product of the values in the subtree of $v$.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(\log n)$
+    pub fn prod_subtree<M: Monoid>(&self, v: usize, segtree: &Segtree<M>) -> M::S {
+        segtree.prod(self.subtree(v))
+    }
+
+    /// Applies $f$ to the values on the path between $u$ and $v$.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(\log^2 n)$
+    pub fn apply_path<F: MapMonoid>(
+        &self,
+        u: usize,
+        v: usize,
+        segtree: &mut LazySegtree<F>,
+        f: F::F,
+    ) {
+        for (range, _) in self.path(u, v) {
+            segtree.apply_range(range, f.clone());
+        }
+    }
+
+    /// Applies $f$ to the values in the subtree of $v$.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(\log n)$
+    pub fn apply_subtree<F: MapMonoid>(&self, v: usize, segtree: &mut LazySegtree<F>, f: F::F) {
+        segtree.apply_range(self.subtree(v), f);
+    }
+
+    fn path_impl(&self, mut u: usize, mut v: usize, edges: bool) -> Vec<(Range<usize>, bool)> {
+        let mut up = vec![];
+        let mut down = vec![];
+        while self.head[u] != self.head[v] {
+            if self.depth[self.head[u]] >= self.depth[self.head[v]] {
+                up.push((self.index[self.head[u]]..self.index[u] + 1, true));
+                u = self.parent[self.head[u]];
+            } else {
+                down.push((self.index[self.head[v]]..self.index[v] + 1, false));
+                v = self.parent[self.head[v]];
+            }
+        }
+        let skip = usize::from(edges);
+        if self.index[u] >= self.index[v] {
+            if self.index[v] + skip <= self.index[u] {
+                up.push((self.index[v] + skip..self.index[u] + 1, true));
+            }
+        } else {
+            down.push((self.index[u] + skip..self.index[v] + 1, false));
+        }
+        up.extend(down.into_iter().rev());
+        up
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modint::ModInt998244353;
+    use crate::segtree::{Additive, Affine, Max};
+    use rand::Rng;
+
+    type Z = ModInt998244353;
+
+    struct MaxAdd;
+    impl MapMonoid for MaxAdd {
+        type M = Max<i64>;
+        type F = i64;
+        fn identity_map() -> i64 {
+            0
+        }
+        fn mapping(&f: &i64, &x: &i64) -> i64 {
+            f + x
+        }
+        fn composition(&f: &i64, &g: &i64) -> i64 {
+            f + g
+        }
+    }
+
+    fn naive_path(parent: &[usize], depth: &[usize], mut u: usize, mut v: usize) -> Vec<usize> {
+        let mut up = vec![];
+        let mut down = vec![];
+        while u != v {
+            if depth[u] >= depth[v] {
+                up.push(u);
+                u = parent[u];
+            } else {
+                down.push(v);
+                v = parent[v];
+            }
+        }
+        up.push(u);
+        up.extend(down.into_iter().rev());
+        up
+    }
+
+    #[test]
+    fn test_hld() {
+        let mut rng = rand::thread_rng();
+        for n in 1..40 {
+            let root = rng.gen_range(0, n);
+            let mut perm = (0..n).collect::<Vec<_>>();
+            for i in 1..n {
+                perm.swap(i, rng.gen_range(0, i + 1));
+            }
+            let edges = (1..n)
+                .map(|i| (perm[rng.gen_range(0, i)], perm[i]))
+                .collect::<Vec<_>>();
+            let hld = Hld::new(n, &edges, root);
+            assert_eq!(hld.len(), n);
+
+            let mut parent = vec![usize::MAX; n];
+            let mut depth = vec![0; n];
+            parent[root] = root;
+            let mut stack = vec![root];
+            while let Some(v) = stack.pop() {
+                for &(a, b) in &edges {
+                    for &(x, y) in &[(a, b), (b, a)] {
+                        if x == v && parent[y] == usize::MAX {
+                            parent[y] = v;
+                            depth[y] = depth[v] + 1;
+                            stack.push(y);
+                        }
+                    }
+                }
+            }
+            for v in 0..n {
+                assert_eq!(hld.vertex(hld.index(v)), v);
+                assert_eq!(hld.depth(v), depth[v]);
+                assert_eq!(hld.parent(v), Some(parent[v]).filter(|_| v != root));
+                let subtree = (0..n)
+                    .filter(|&w| {
+                        naive_path(&parent, &depth, v, w)[1..]
+                            .iter()
+                            .all(|&x| depth[x] > depth[v])
+                    })
+                    .map(|w| hld.index(w))
+                    .collect::<std::collections::BTreeSet<_>>();
+                assert_eq!(subtree, hld.subtree(v).collect());
+            }
+
+            let values = (0..n)
+                .map(|_| (Z::new(rng.gen_range(1, 100)), Z::new(rng.gen_range(0, 100))))
+                .collect::<Vec<_>>();
+            let mut by_index = vec![values[0]; n];
+            for v in 0..n {
+                by_index[hld.index(v)] = values[v];
+            }
+            let segtree: Segtree<Affine<Z>> = by_index.iter().copied().collect();
+            let reversed: Segtree<Reversed<Affine<Z>>> = by_index.iter().copied().collect();
+            let sum: Segtree<Additive<u32>> = by_index.iter().map(|x| x.1.val()).collect();
+            let mut lazy: LazySegtree<MaxAdd> = vec![0; n].into();
+            let mut naive_lazy = vec![0; n];
+
+            for _ in 0..50 {
+                let (u, v) = (rng.gen_range(0, n), rng.gen_range(0, n));
+                let path = naive_path(&parent, &depth, u, v);
+                let lca = *path.iter().min_by_key(|&&w| depth[w]).unwrap();
+                assert_eq!(hld.lca(u, v), lca);
+                assert_eq!(hld.dist(u, v), path.len() - 1);
+
+                let flattened = |ranges: Vec<(Range<usize>, bool)>| {
+                    ranges
+                        .into_iter()
+                        .flat_map(|(range, rev)| {
+                            let mut v = range.collect::<Vec<_>>();
+                            if rev {
+                                v.reverse();
+                            }
+                            v
+                        })
+                        .map(|i| hld.vertex(i))
+                        .collect::<Vec<_>>()
+                };
+                assert_eq!(flattened(hld.path(u, v)), path);
+                let edges = path
+                    .iter()
+                    .copied()
+                    .filter(|&w| w != lca)
+                    .collect::<Vec<_>>();
+                assert_eq!(flattened(hld.path_edges(u, v)), edges);
+
+                let x = Z::new(12345);
+                let (a, b) = hld.prod_path(u, v, &segtree, &reversed);
+                let expected = path.iter().fold(x, |x, &w| values[w].0 * x + values[w].1);
+                assert_eq!(a * x + b, expected);
+                assert_eq!(
+                    hld.prod_path_commutative(u, v, &sum),
+                    path.iter().map(|&w| values[w].1.val()).sum::<u32>()
+                );
+                assert_eq!(
+                    hld.prod_subtree(u, &sum),
+                    hld.subtree(u).map(|i| by_index[i].1.val()).sum::<u32>()
+                );
+
+                let f = rng.gen_range(-10, 10);
+                if rng.gen() {
+                    hld.apply_path(u, v, &mut lazy, f);
+                    for &w in &path {
+                        naive_lazy[w] += f;
+                    }
+                } else {
+                    hld.apply_subtree(u, &mut lazy, f);
+                    for i in hld.subtree(u) {
+                        naive_lazy[hld.vertex(i)] += f;
+                    }
+                }
+                for (w, &x) in naive_lazy.iter().enumerate() {
+                    assert_eq!(lazy.get(hld.index(w)), x);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_deep_path() {
+        let n = 200_000;
+        let edges = (1..n).map(|i| (i - 1, i)).collect::<Vec<_>>();
+        let hld = Hld::new(n, &edges, 0);
+        assert_eq!(hld.lca(n - 1, n / 2), n / 2);
+        assert_eq!(hld.path(n - 1, 0), vec![(0..n, true)]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_not_a_tree() {
+        Hld::new(4, &[(0, 1), (1, 0), (2, 3)], 0);
+    }
+}
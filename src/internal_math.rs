@@ -141,6 +141,72 @@ pub(crate) fn is_prime(n: i32) -> bool {
     true
 }
 
+/// # Parameters
+/// * `m` `1 <= m`
+///
+/// # Returns
+/// `a * b % m`, computed without overflow
+#[allow(clippy::many_single_char_names)]
+pub(crate) fn mul_mod_u64(a: u64, b: u64, m: u64) -> u64 {
+    (a as u128 * b as u128 % m as u128) as u64
+}
+
+/// # Parameters
+/// * `m` `1 <= m`
+///
+/// # Returns
+/// `(x ** n) % m`
+#[allow(clippy::many_single_char_names)]
+pub(crate) fn pow_mod_u64(x: u64, mut n: u64, m: u64) -> u64 {
+    if m == 1 {
+        return 0;
+    }
+    let mut r = 1;
+    let mut y = x % m;
+    while n != 0 {
+        if n & 1 != 0 {
+            r = mul_mod_u64(r, y, m);
+        }
+        y = mul_mod_u64(y, y, m);
+        n >>= 1;
+    }
+    r
+}
+
+/// Deterministic Miller-Rabin test for 64-bit integers.
+///
+/// Reference:
+/// https://miller-rabin.appspot.com/ (the 7 bases found by Jim Sinclair)
+pub(crate) fn is_prime_u64(n: u64) -> bool {
+    match n {
+        _ if n <= 1 => return false,
+        2 | 3 | 5 | 7 => return true,
+        _ if n % 2 == 0 || n % 3 == 0 || n % 5 == 0 || n % 7 == 0 => return false,
+        _ if n < 121 => return true,
+        _ => {}
+    }
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+    'outer: for &a in &[2, 325, 9375, 28178, 450775, 9780504, 1795265022] {
+        let a = a % n;
+        if a == 0 {
+            continue;
+        }
+        let mut y = pow_mod_u64(a, d, n);
+        if y == 1 || y == n - 1 {
+            continue;
+        }
+        for _ in 1..s {
+            y = mul_mod_u64(y, y, n);
+            if y == n - 1 {
+                continue 'outer;
+            }
+        }
+        return false;
+    }
+    true
+}
+
 // omitted
 // template <int n> constexpr bool is_prime = is_prime_constexpr(n);
 
@@ -187,6 +253,33 @@ pub(crate) fn inv_gcd(a: i64, b: i64) -> (i64, i64) {
     (s, m0)
 }
 
+/// `i128` version of [`inv_gcd`].
+#[allow(clippy::many_single_char_names)]
+pub(crate) fn inv_gcd_i128(a: i128, b: i128) -> (i128, i128) {
+    let a = a.rem_euclid(b);
+    if a == 0 {
+        return (b, 0);
+    }
+
+    // the same contracts as `inv_gcd` hold
+    let mut s = b;
+    let mut t = a;
+    let mut m0 = 0;
+    let mut m1 = 1;
+
+    while t != 0 {
+        let u = s / t;
+        s -= t * u;
+        m0 -= m1 * u;
+        swap(&mut s, &mut t);
+        swap(&mut m0, &mut m1);
+    }
+    if m0 < 0 {
+        m0 += b / s;
+    }
+    (s, m0)
+}
+
 /// Compile time (currently not) primitive root
 /// @param m must be prime
 /// @return primitive root (and minimum in now)
@@ -279,7 +372,9 @@ pub(crate) fn floor_sum_unsigned(
 mod tests {
     #![allow(clippy::unreadable_literal)]
     #![allow(clippy::cognitive_complexity)]
-    use crate::internal_math::{inv_gcd, is_prime, pow_mod, primitive_root, safe_mod, Barrett};
+    use crate::internal_math::{
+        inv_gcd, is_prime, is_prime_u64, pow_mod, primitive_root, safe_mod, Barrett,
+    };
     use std::collections::HashSet;
 
     #[test]
@@ -422,6 +517,35 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_is_prime_u64() {
+        let n = 100_000;
+        let mut prime = vec![true; n];
+        prime[0] = false;
+        prime[1] = false;
+        for i in 0..n {
+            assert_eq!(prime[i], is_prime_u64(i as u64));
+            if prime[i] {
+                for j in (2 * i..n).step_by(i) {
+                    prime[j] = false;
+                }
+            }
+        }
+        for n in (i32::MAX as u64 - 20_000)..=(i32::MAX as u64) {
+            assert_eq!(is_prime_u64(n), is_prime(n as i32));
+        }
+
+        assert!(is_prime_u64(998_244_353));
+        assert!(is_prime_u64(1_000_000_007));
+        assert!(is_prime_u64(1_000_000_000_000_000_003));
+        assert!(is_prime_u64(18_446_744_073_709_551_557)); // 2^64 - 59
+        assert!(!is_prime_u64(u64::MAX));
+        assert!(!is_prime_u64(1_000_000_007 * 998_244_353));
+        // strong pseudoprimes to several small bases
+        assert!(!is_prime_u64(3_215_031_751));
+        assert!(!is_prime_u64(3_825_123_056_546_413_051));
+    }
+
     #[test]
     fn test_inv_gcd() {
         for &(a, b, g) in &[
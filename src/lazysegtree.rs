@@ -16,6 +16,13 @@ pub trait MapMonoid {
     }
     fn identity_map() -> Self::F;
     fn mapping(f: &Self::F, x: &<Self::M as Monoid>::S) -> <Self::M as Monoid>::S;
+    /// Applies $f$ to $x$, or returns `None` if the result cannot be computed from $x$ alone.
+    ///
+    /// If it returns `None`, the tree pushes $f$ down to the children and recomputes the node from them, as in Segment Tree Beats.
+    /// It must not fail on the elements themselves.
+    fn try_mapping(f: &Self::F, x: &<Self::M as Monoid>::S) -> Option<<Self::M as Monoid>::S> {
+        Some(Self::mapping(f, x))
+    }
     fn composition(f: &Self::F, g: &Self::F) -> Self::F;
 }
 
@@ -310,9 +317,19 @@ where
         self.d[k] = F::binary_operation(&self.d[2 * k], &self.d[2 * k + 1]);
     }
     fn all_apply(&mut self, k: usize, f: F::F) {
-        self.d[k] = F::mapping(&f, &self.d[k]);
-        if k < self.size {
-            self.lz[k] = F::composition(&f, &self.lz[k]);
+        match F::try_mapping(&f, &self.d[k]) {
+            Some(x) => {
+                self.d[k] = x;
+                if k < self.size {
+                    self.lz[k] = F::composition(&f, &self.lz[k]);
+                }
+            }
+            None => {
+                assert!(k < self.size, "the mapping failed on an element");
+                self.lz[k] = F::composition(&f, &self.lz[k]);
+                self.push(k);
+                self.update(k);
+            }
         }
     }
     fn push(&mut self, k: usize) {
@@ -1,15 +1,28 @@
+pub mod convexhulltrick;
 pub mod convolution;
+pub mod dirichlet;
 pub mod dsu;
+pub mod dualsegtree;
+pub mod dynamicsegtree;
 pub mod fenwicktree;
+pub mod hld;
 pub mod lazysegtree;
+pub mod linear_recurrence;
 pub mod math;
 pub mod maxflow;
 pub mod mincostflow;
 pub mod modint;
+pub mod persistentsegtree;
+pub mod poly;
 pub mod scc;
 pub mod segtree;
+pub mod segtree2d;
+pub mod segtreebeats;
+pub mod sparsetable;
 pub mod string;
 pub mod twosat;
+pub mod universal_euclidean;
+pub mod waveletmatrix;
 
 mod internal_bit;
 mod internal_math;
@@ -17,23 +30,48 @@ mod internal_queue;
 mod internal_scc;
 mod internal_type_traits;
 
-pub use convolution::{convolution, convolution_i64};
+pub use convexhulltrick::{LiChaoTree, LineValue, MonotoneCht};
+pub use convolution::{
+    convolution, convolution_2d, convolution_i64, convolution_nd, multivariate_convolution,
+    multivariate_convolution_total_degree, OnlineConvolution,
+};
+pub use dirichlet::{dirichlet_convolution, dirichlet_inverse};
 pub use dsu::Dsu;
+pub use dualsegtree::DualSegtree;
+pub use dynamicsegtree::DynamicSegtree;
 pub use fenwicktree::FenwickTree;
+pub use hld::Hld;
 pub use lazysegtree::{LazySegtree, MapMonoid};
-pub use math::{crt, floor_sum, inv_mod, pow_mod};
+pub use linear_recurrence::{berlekamp_massey, linear_recurrence_nth, nth_term_of_rational};
+pub use math::{
+    ceil_quotient_ranges, crt, crt_i128, divisors, ext_gcd, factorize, floor_sum, garner, inv_mod,
+    inv_mod_u64, iroot, is_prime_u64, isqrt, multiplicative_prefix_sum, pow_mod, pow_mod_u64,
+    prime_count, prime_sum_table, quotient_ranges, segmented_sieve, solve_linear_diophantine,
+    LinearDiophantine, QuotientRanges, Sieve,
+};
 pub use maxflow::{Edge, MfGraph};
 pub use mincostflow::MinCostFlowGraph;
 pub use modint::{
     Barrett, ButterflyCache, DefaultId, DynamicModInt, Id, Mod1000000007, Mod998244353, ModInt,
     ModInt1000000007, ModInt998244353, Modulus, RemEuclidU32, StaticModInt,
 };
+pub use persistentsegtree::{PersistentSegtree, Version};
+pub use poly::{
+    bernoulli, chirp_z, lagrange_interpolation_at, partition_numbers, shift_of_sampling_points,
+    stirling1_column, stirling1_row, stirling2_column, stirling2_row, sum_of_powers,
+};
 pub use scc::SccGraph;
 pub use segtree::{
-    Additive, BitwiseAnd, BitwiseOr, BitwiseXor, Max, Min, Monoid, Multiplicative, Segtree,
+    Additive, Affine, ArgMax, ArgMin, BitwiseAnd, BitwiseOr, BitwiseXor, Gcd, MatrixProduct, Max,
+    Min, Monoid, Multiplicative, Reversed, Segtree,
 };
+pub use segtree2d::{CompressedSegtree2D, Segtree2D};
+pub use segtreebeats::{BeatsMap, BeatsMonoid, BeatsNode, RangeChminChmaxAdd, SegtreeBeats};
+pub use sparsetable::{DisjointSparseTable, Idempotent, SparseTable};
 pub use string::{
     lcp_array, lcp_array_arbitrary, suffix_array, suffix_array_arbitrary, suffix_array_manual,
     z_algorithm, z_algorithm_arbitrary,
 };
 pub use twosat::TwoSat;
+pub use universal_euclidean::{floor_sums, universal_euclidean};
+pub use waveletmatrix::{WaveletMatrix, WaveletMatrixWithSum};
@@ -0,0 +1,287 @@
+//! Functions that find and evaluate linear recurrences.
+//!
+//! A sequence $a_0, a_1, \ldots$ satisfies a linear recurrence of order $d$ if there are $c_1, c_2, \ldots, c_d$ such that
+//!
+//! \\[
+//!   a_i = \sum_{j = 1}^d c_j a_{i - j} \quad (i \geq d).
+//! \\]
+//!
+//! Equivalently, its generating function is $P(x) / Q(x)$ where $Q(x) = 1 - \sum_{j = 1}^d c_j x^j$ and $\deg P < d$.
+
+use crate::{
+    convolution::convolution,
+    modint::{ModIntBase, Modulus, StaticModInt},
+};
+
+/// Finds the shortest linear recurrence that generates `s`, by the Berlekamp–Massey algorithm.
+///
+/// Returns $c_1, c_2, \ldots, c_d$ with the minimum $d$ such that
+///
+/// \\[
+///   s_i = \sum_{j = 1}^d c_j s_{i - j} \quad (d \leq i < |s|).
+/// \\]
+///
+/// If you want to recover a recurrence of order $d$, $|s| \geq 2d$ terms are needed.
+///
+/// # Constraints
+///
+/// - `Z::modulus()` is a prime number.
+///
+/// # Complexity
+///
+/// - $O(n^2)$ where $n = |s|$.
+///
+/// # Example
+///
+/// ```
+/// use ac_library::{linear_recurrence::berlekamp_massey, ModInt998244353 as Mint};
+///
+/// let s = [1, 1, 2, 3, 5, 8, 13].map(Mint::new);
+/// assert_eq!(berlekamp_massey(&s), [Mint::new(1), Mint::new(1)]);
+/// ```
+#[allow(clippy::many_single_char_names)]
+pub fn berlekamp_massey<Z: ModIntBase>(s: &[Z]) -> Vec<Z> {
+    // Both are stored as the coefficients of the connection polynomials 1 - c_1 x - c_2 x^2 - ...
+    let mut c = vec![Z::new(1)];
+    let mut b = vec![Z::new(1)];
+    let mut l = 0;
+    // `m` is the number of steps since `b` was updated, `bd` is the discrepancy at that time.
+    let mut m = 1;
+    let mut bd = Z::new(1);
+    for i in 0..s.len() {
+        let d = (0..=l).fold(Z::new(0), |acc, j| acc + c[j] * s[i - j]);
+        if d == Z::new(0) {
+            m += 1;
+            continue;
+        }
+        let coef = d / bd;
+        if 2 * l <= i {
+            let t = c.clone();
+            if c.len() < b.len() + m {
+                c.resize(b.len() + m, Z::new(0));
+            }
+            for (j, &bj) in b.iter().enumerate() {
+                c[j + m] -= coef * bj;
+            }
+            l = i + 1 - l;
+            b = t;
+            bd = d;
+            m = 1;
+        } else {
+            if c.len() < b.len() + m {
+                c.resize(b.len() + m, Z::new(0));
+            }
+            for (j, &bj) in b.iter().enumerate() {
+                c[j + m] -= coef * bj;
+            }
+            m += 1;
+        }
+    }
+    c.resize(l + 1, Z::new(0));
+    c.into_iter().skip(1).map(|x| -x).collect()
+}
+
+/// Returns $[x^n] P(x) / Q(x)$, by the Bostan–Mori algorithm.
+///
+/// # Constraints
+///
+/// - $Q_0 \neq 0$
+/// - `M::VALUE` satisfies the constraints of [`convolution`].
+///
+/// # Panics
+///
+/// Panics if `q` is empty or $Q_0 = 0$.
+///
+/// # Complexity
+///
+/// - $O(d \log d \log n)$ where $d = \max(|p|, |q|)$.
+///
+/// # Example
+///
+/// ```
+/// use ac_library::{linear_recurrence::nth_term_of_rational, ModInt998244353 as Mint};
+///
+/// // 1 / (1 - x)^2 = 1 + 2x + 3x^2 + ...
+/// let p = [Mint::new(1)];
+/// let q = [Mint::new(1), Mint::new(-2), Mint::new(1)];
+/// assert_eq!(nth_term_of_rational(&p, &q, 99), Mint::new(100));
+/// ```
+///
+/// [`convolution`]: ../convolution/fn.convolution.html
+pub fn nth_term_of_rational<M: Modulus>(
+    p: &[StaticModInt<M>],
+    q: &[StaticModInt<M>],
+    mut n: u64,
+) -> StaticModInt<M> {
+    assert!(!q.is_empty() && q[0].val() != 0);
+    let mut p = p.to_vec();
+    let mut q = q.to_vec();
+    while n > 0 && !p.is_empty() {
+        let q_neg = q
+            .iter()
+            .enumerate()
+            .map(|(i, &x)| if i % 2 == 0 { x } else { -x })
+            .collect::<Vec<_>>();
+        let u = convolution(&p, &q_neg);
+        let v = convolution(&q, &q_neg);
+        let parity = (n % 2) as usize;
+        p = u.into_iter().skip(parity).step_by(2).collect();
+        q = v.into_iter().step_by(2).collect();
+        n /= 2;
+    }
+    if n > 0 || p.is_empty() {
+        return StaticModInt::raw(0);
+    }
+    p[0] / q[0]
+}
+
+/// Returns $a_n$ where $a$ satisfies $a_i = \sum_{j = 1}^d c_j a_{i - j}\ (i \geq d)$.
+///
+/// Only the first $d = |c|$ elements of `a` are used as the initial terms.
+/// `c` is in the same form as the return value of [`berlekamp_massey`].
+///
+/// # Constraints
+///
+/// - $|a| \geq |c|$
+/// - `M::VALUE` satisfies the constraints of [`convolution`].
+///
+/// # Panics
+///
+/// Panics if $|a| < |c|$.
+///
+/// # Complexity
+///
+/// - $O(d \log d \log n)$
+///
+/// # Example
+///
+/// ```
+/// use ac_library::{linear_recurrence::linear_recurrence_nth, ModInt1000000007 as Mint};
+///
+/// // Fibonacci numbers
+/// let a = [Mint::new(0), Mint::new(1)];
+/// let c = [Mint::new(1), Mint::new(1)];
+/// assert_eq!(linear_recurrence_nth(&a, &c, 100), Mint::new(687_995_182));
+/// ```
+///
+/// [`berlekamp_massey`]: ./fn.berlekamp_massey.html
+/// [`convolution`]: ../convolution/fn.convolution.html
+pub fn linear_recurrence_nth<M: Modulus>(
+    a: &[StaticModInt<M>],
+    c: &[StaticModInt<M>],
+    n: u64,
+) -> StaticModInt<M> {
+    let d = c.len();
+    assert!(a.len() >= d);
+    if d == 0 {
+        return StaticModInt::raw(0);
+    }
+    let q = std::iter::once(StaticModInt::new(1))
+        .chain(c.iter().map(|&x| -x))
+        .collect::<Vec<_>>();
+    let mut p = convolution(&a[..d], &q);
+    p.truncate(d);
+    nth_term_of_rational(&p, &q, n)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modint::{ModInt, ModInt998244353};
+    use rand::Rng as _;
+
+    type Mint = ModInt998244353;
+
+    fn generate(a: &[Mint], c: &[Mint], n: usize) -> Vec<Mint> {
+        let mut s = a[..c.len()].to_vec();
+        while s.len() < n {
+            let i = s.len();
+            let next = (1..=c.len()).map(|j| c[j - 1] * s[i - j]).sum();
+            s.push(next);
+        }
+        s.truncate(n);
+        s
+    }
+
+    #[test]
+    fn test_berlekamp_massey_small() {
+        assert!(berlekamp_massey::<Mint>(&[]).is_empty());
+        assert!(berlekamp_massey(&[Mint::new(0); 5]).is_empty());
+        assert_eq!(berlekamp_massey(&[Mint::new(3)]).len(), 1);
+        assert_eq!(
+            berlekamp_massey(&[1, 2, 4, 8, 16].map(Mint::new)),
+            [Mint::new(2)]
+        );
+        assert_eq!(berlekamp_massey(&[0, 0, 0, 1].map(Mint::new)).len(), 4);
+    }
+
+    #[test]
+    fn test_berlekamp_massey_dynamic() {
+        // the default modulus of `ModInt` is 998244353
+        let s = [1, 1, 2, 3, 5, 8, 13, 21].map(ModInt::new);
+        assert_eq!(berlekamp_massey(&s), [ModInt::new(1), ModInt::new(1)]);
+    }
+
+    #[test]
+    fn test_berlekamp_massey_random() {
+        let mut rng = rand::thread_rng();
+        for d in 1..20 {
+            let a = (0..d)
+                .map(|_| Mint::new(rng.gen::<u32>()))
+                .collect::<Vec<_>>();
+            let mut c = (0..d)
+                .map(|_| Mint::new(rng.gen::<u32>()))
+                .collect::<Vec<_>>();
+            if c[d - 1].val() == 0 {
+                c[d - 1] = Mint::new(1);
+            }
+            let s = generate(&a, &c, 2 * d + 5);
+            let found = berlekamp_massey(&s);
+            assert!(found.len() <= d);
+            assert_eq!(generate(&s, &found, s.len()), s);
+        }
+    }
+
+    #[test]
+    fn test_nth_term_of_rational() {
+        // 1 / (1 - x - x^2)
+        let q = [1, -1, -1].map(Mint::new);
+        let p = [Mint::new(1)];
+        let fib = generate(&[Mint::new(1), Mint::new(1)], &[Mint::new(1); 2], 100);
+        for (n, &f) in fib.iter().enumerate() {
+            assert_eq!(nth_term_of_rational(&p, &q, n as u64), f);
+        }
+        assert_eq!(nth_term_of_rational(&[], &q, 10), Mint::new(0));
+        // polynomial numerator of higher degree than the denominator
+        let p = [1, 2, 3, 4].map(Mint::new);
+        let q = [Mint::new(2)];
+        for n in 0..10 {
+            let expected = p.get(n).map_or(Mint::new(0), |&x| x / Mint::new(2));
+            assert_eq!(nth_term_of_rational(&p, &q, n as u64), expected);
+        }
+    }
+
+    #[test]
+    fn test_linear_recurrence_nth() {
+        let mut rng = rand::thread_rng();
+        for d in 0..30 {
+            let a = (0..d)
+                .map(|_| Mint::new(rng.gen::<u32>()))
+                .collect::<Vec<_>>();
+            let c = (0..d)
+                .map(|_| Mint::new(rng.gen::<u32>()))
+                .collect::<Vec<_>>();
+            let s = generate(&a, &c, 100);
+            for (n, &expected) in s.iter().enumerate() {
+                assert_eq!(linear_recurrence_nth(&a, &c, n as u64), expected);
+            }
+        }
+        let a = [Mint::new(0), Mint::new(1)];
+        let c = [Mint::new(1), Mint::new(1)];
+        // F_{10^18} mod 998244353
+        let x = linear_recurrence_nth(&a, &c, 1_000_000_000_000_000_000);
+        let y = linear_recurrence_nth(&a, &c, 1_000_000_000_000_000_001);
+        let z = linear_recurrence_nth(&a, &c, 1_000_000_000_000_000_002);
+        assert_eq!(x + y, z);
+    }
+}
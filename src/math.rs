@@ -1,8 +1,14 @@
 //! Number-theoretic algorithms.
 
 use crate::internal_math;
+use crate::internal_type_traits::Integral;
 
-use std::mem::swap;
+use std::{
+    cmp::Ordering,
+    convert::TryFrom,
+    mem::swap,
+    ops::{Add, Mul, Neg, Range, RangeInclusive, Sub},
+};
 
 /// Returns $x^n \bmod m$.
 ///
@@ -74,6 +80,328 @@ pub fn inv_mod(x: i64, m: i64) -> i64 {
     z.1
 }
 
+/// Returns $x^n \bmod m$ for a 64-bit modulus.
+///
+/// The intermediate products are computed in `u128`, so any $m < 2^{64}$ is allowed, unlike [`pow_mod`].
+///
+/// # Constraints
+///
+/// - $1 \leq m$
+///
+/// # Panics
+///
+/// Panics if the above constraints are not satisfied.
+///
+/// # Complexity
+///
+/// - $O(\log n)$
+///
+/// # Example
+///
+/// ```
+/// use ac_library::math;
+///
+/// assert_eq!(math::pow_mod_u64(2, 10000, 7), 2);
+/// // 2^64 - 59 is a prime
+/// assert_eq!(math::pow_mod_u64(3, u64::MAX - 59, u64::MAX - 58), 1);
+/// ```
+///
+/// [`pow_mod`]: ./fn.pow_mod.html
+pub fn pow_mod_u64(x: u64, n: u64, m: u64) -> u64 {
+    assert!(1 <= m);
+    internal_math::pow_mod_u64(x, n, m)
+}
+
+/// Returns an integer $y \in [0, m)$ such that $xy \equiv 1 \pmod m$, for a 64-bit modulus.
+///
+/// # Constraints
+///
+/// - $\gcd(x, m) = 1$
+/// - $1 \leq m$
+///
+/// # Panics
+///
+/// Panics if the above constraints are not satisfied.
+///
+/// # Complexity
+///
+/// - $O(\log m)$
+///
+/// # Example
+///
+/// ```
+/// use ac_library::math;
+///
+/// assert_eq!(math::inv_mod_u64(3, 7), 5);
+/// assert_eq!(math::inv_mod_u64(2, u64::MAX), u64::MAX / 2 + 1);
+/// ```
+pub fn inv_mod_u64(x: u64, m: u64) -> u64 {
+    assert!(1 <= m);
+    let z = internal_math::inv_gcd_i128(x as i128, m as i128);
+    assert!(z.0 == 1);
+    z.1 as u64
+}
+
+/// Returns $(g, x, y)$ such that $g = \gcd(a, b)$ and $ax + by = g$.
+///
+/// $g$ is non-negative.
+/// If $a = b = 0$, it returns $(0, 1, 0)$.
+///
+/// # Constraints
+///
+/// - $a, b$ are not the minimum value of `T`
+///
+/// # Complexity
+///
+/// - $O(\log \min(|a|, |b|))$
+///
+/// # Example
+///
+/// ```
+/// use ac_library::math;
+///
+/// assert_eq!(math::ext_gcd(12i64, 18), (6, -1, 1));
+/// assert_eq!(math::ext_gcd(-4i32, 6), (2, 1, 1));
+/// ```
+#[allow(clippy::many_single_char_names)]
+pub fn ext_gcd<T>(a: T, b: T) -> (T, T, T)
+where
+    T: Integral + Neg<Output = T>,
+{
+    // Contracts:
+    // [1] a * x0 + b * y0 = s
+    // [2] a * x1 + b * y1 = t
+    let (mut s, mut t) = (a, b);
+    let (mut x0, mut x1) = (T::one(), T::zero());
+    let (mut y0, mut y1) = (T::zero(), T::one());
+    while t != T::zero() {
+        let u = s / t;
+        s -= t * u;
+        x0 -= x1 * u;
+        y0 -= y1 * u;
+        swap(&mut s, &mut t);
+        swap(&mut x0, &mut x1);
+        swap(&mut y0, &mut y1);
+    }
+    if s < T::zero() {
+        (-s, -x0, -y0)
+    } else {
+        (s, x0, y0)
+    }
+}
+
+/// Returns $\lfloor \sqrt{n} \rfloor$.
+///
+/// # Constraints
+///
+/// - $0 \leq n$
+///
+/// # Panics
+///
+/// Panics if the above constraints are not satisfied.
+///
+/// # Complexity
+///
+/// - $O(\log n)$
+///
+/// # Example
+///
+/// ```
+/// use ac_library::math;
+///
+/// assert_eq!(math::isqrt(99u64), 9);
+/// assert_eq!(math::isqrt(u128::MAX), u64::MAX as u128);
+/// ```
+pub fn isqrt<T: Integral>(n: T) -> T {
+    iroot(n, 2)
+}
+
+/// Returns $\lfloor n^{1/k} \rfloor$, i.e. the largest $x$ such that $x^k \leq n$.
+///
+/// The result is exact; no floating point arithmetic is used.
+///
+/// # Constraints
+///
+/// - $0 \leq n$
+/// - $1 \leq k$
+///
+/// # Panics
+///
+/// Panics if the above constraints are not satisfied.
+///
+/// # Complexity
+///
+/// - $O(b \min(k, b))$, where $b$ is the bit width of `T`
+///
+/// # Example
+///
+/// ```
+/// use ac_library::math;
+///
+/// assert_eq!(math::iroot(1_000_000_000_000_000_000u64, 3), 1_000_000);
+/// assert_eq!(math::iroot(999_999_999_999_999_999u64, 3), 999_999);
+/// assert_eq!(math::iroot(u128::MAX, 64), 3);
+/// ```
+pub fn iroot<T: Integral>(n: T, k: u32) -> T {
+    assert!(T::zero() <= n && 1 <= k);
+    // `x^k <= n` without overflow
+    let pow_le = |x: T| {
+        let mut p = T::one();
+        for _ in 0..k {
+            if p > n / x {
+                return false;
+            }
+            p *= x;
+        }
+        true
+    };
+    let mut bits = 0;
+    let mut m = T::max_value();
+    while m != T::zero() {
+        m >>= T::one();
+        bits += 1;
+    }
+    // x < 2^ceil(bits / k)
+    let mut bit = T::one();
+    for _ in 1..(bits + k - 1) / k {
+        bit += bit;
+    }
+    let mut x = T::zero();
+    while bit != T::zero() {
+        if pow_le(x | bit) {
+            x |= bit;
+        }
+        bit >>= T::one();
+    }
+    x
+}
+
+/// Solutions of a linear Diophantine equation $ax + by = c$, returned by [`solve_linear_diophantine`].
+///
+/// The solutions are exactly $(x + t \cdot dx, y + t \cdot dy)$ for $t \in \mathbb{Z}$.
+/// It is normalized so that $dx \geq 0$, and
+///
+/// - if $dx > 0$, $0 \leq x < dx$
+/// - if $dx = 0$, $dy > 0$ and $0 \leq y < dy$
+///
+/// [`solve_linear_diophantine`]: ./fn.solve_linear_diophantine.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LinearDiophantine {
+    pub x: i64,
+    pub y: i64,
+    pub dx: i64,
+    pub dy: i64,
+}
+
+impl LinearDiophantine {
+    /// Returns the $t$-th solution $(x + t \cdot dx, y + t \cdot dy)$.
+    ///
+    /// # Panics
+    ///
+    /// Panics if overflow occurred.
+    pub fn nth(&self, t: i64) -> (i64, i64) {
+        let f = |base: i64, d: i64| i64::try_from(base as i128 + t as i128 * d as i128).unwrap();
+        (f(self.x, self.dx), f(self.y, self.dy))
+    }
+
+    /// Returns the number of solutions $(x, y)$ such that $x_{\mathrm{lo}} \leq x \leq x_{\mathrm{hi}}$ and $y_{\mathrm{lo}} \leq y \leq y_{\mathrm{hi}}$.
+    ///
+    /// It can be $2^{64}$, so it is returned as `u128`.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(1)$
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ac_library::math;
+    ///
+    /// // 2x + 3y = 12 with x, y >= 0: (0, 4), (3, 2), (6, 0)
+    /// let s = math::solve_linear_diophantine(2, 3, 12).unwrap();
+    /// assert_eq!(s.count(0..=12, 0..=12), 3);
+    /// ```
+    pub fn count(&self, x: RangeInclusive<i64>, y: RangeInclusive<i64>) -> u128 {
+        // the range of t such that lo <= base + t * d <= hi
+        fn range(base: i64, d: i64, lo: i64, hi: i64) -> (i128, i128) {
+            let (base, d, lo, hi) = (base as i128, d as i128, lo as i128, hi as i128);
+            match d.cmp(&0) {
+                Ordering::Equal if lo <= base && base <= hi => (i128::MIN, i128::MAX),
+                Ordering::Equal => (1, 0),
+                Ordering::Greater => (-(base - lo).div_euclid(d), (hi - base).div_euclid(d)),
+                Ordering::Less => (-(hi - base).div_euclid(-d), (base - lo).div_euclid(-d)),
+            }
+        }
+        let (lx, rx) = range(self.x, self.dx, *x.start(), *x.end());
+        let (ly, ry) = range(self.y, self.dy, *y.start(), *y.end());
+        let (l, r) = (lx.max(ly), rx.min(ry));
+        if l > r {
+            0
+        } else {
+            (r - l + 1) as u128
+        }
+    }
+}
+
+/// Solves a linear Diophantine equation $ax + by = c$.
+///
+/// If there is no solution, it returns `None`.
+/// Otherwise, it returns all of the solutions as a one-parameter family. See [`LinearDiophantine`].
+///
+/// # Constraints
+///
+/// - $(a, b) \neq (0, 0)$
+/// - The normalized $(x, y)$ and the step $(d_x, d_y)$ are in `i64`
+///
+/// # Panics
+///
+/// Panics if the above constraints are not satisfied.
+///
+/// # Complexity
+///
+/// - $O(\log \min(|a|, |b|))$
+///
+/// # Example
+///
+/// ```
+/// use ac_library::math::{self, LinearDiophantine};
+///
+/// assert_eq!(
+///     math::solve_linear_diophantine(4, 6, 10),
+///     Some(LinearDiophantine { x: 1, y: 1, dx: 3, dy: -2 }),
+/// );
+/// assert_eq!(math::solve_linear_diophantine(4, 6, 9), None);
+/// ```
+///
+/// [`LinearDiophantine`]: ./struct.LinearDiophantine.html
+#[allow(clippy::many_single_char_names)]
+pub fn solve_linear_diophantine(a: i64, b: i64, c: i64) -> Option<LinearDiophantine> {
+    assert!(a != 0 || b != 0);
+    let (a, b, c) = (a as i128, b as i128, c as i128);
+    let (g, x0, y0) = ext_gcd(a, b);
+    if c % g != 0 {
+        return None;
+    }
+    let (mut dx, mut dy) = (b / g, -a / g);
+    if dx < 0 || dx == 0 && dy < 0 {
+        dx = -dx;
+        dy = -dy;
+    }
+    let (x, y) = if dx != 0 {
+        let x = (x0 % dx * (c / g % dx)).rem_euclid(dx);
+        (x, (c - a * x) / b)
+    } else {
+        let y = (y0 % dy * (c / g % dy)).rem_euclid(dy);
+        ((c - b * y) / a, y)
+    };
+    Some(LinearDiophantine {
+        x: i64::try_from(x).unwrap(),
+        y: i64::try_from(y).unwrap(),
+        dx: i64::try_from(dx).unwrap(),
+        dy: i64::try_from(dy).unwrap(),
+    })
+}
+
 /// Performs CRT (Chinese Remainder Theorem).
 ///
 /// Given two sequences $r, m$ of length $n$, this function solves the modular equation system
@@ -162,6 +490,133 @@ pub fn crt(r: &[i64], m: &[i64]) -> (i64, i64) {
     (r0, m0)
 }
 
+/// Performs CRT (Chinese Remainder Theorem) in `i128`.
+///
+/// This is the same as [`crt`] except that the combined modulus $\text{lcm}(m)$ may exceed the range of `i64`.
+///
+/// If there is no solution, it returns $(0, 0)$.
+///
+/// # Constraints
+///
+/// - $|r| = |m|$
+/// - $1 \leq m_{\forall i}$
+/// - $\text{lcm}(m)$ is in `i128`
+///
+/// # Panics
+///
+/// Panics if the above constraints are not satisfied.
+///
+/// # Complexity
+///
+/// - $O(n \log \text{lcm}(m))$
+///
+/// # Example
+///
+/// ```
+/// use ac_library::math;
+///
+/// let r = [1, 2];
+/// let m = [1_000_000_000_000_000_003, 1_000_000_000_000_000_009];
+/// let (y, z) = math::crt_i128(&r, &m);
+/// assert_eq!(z, 1_000_000_000_000_000_012_000_000_000_000_000_027);
+/// assert_eq!((y % m[0], y % m[1]), (1, 2));
+/// ```
+///
+/// [`crt`]: ./fn.crt.html
+pub fn crt_i128(r: &[i128], m: &[i128]) -> (i128, i128) {
+    assert_eq!(r.len(), m.len());
+    // Contracts: 0 <= r0 < m0
+    let (mut r0, mut m0) = (0, 1);
+    for (&(mut ri), &(mut mi)) in r.iter().zip(m.iter()) {
+        assert!(1 <= mi);
+        ri = ri.rem_euclid(mi);
+        if m0 < mi {
+            swap(&mut r0, &mut ri);
+            swap(&mut m0, &mut mi);
+        }
+        if m0 % mi == 0 {
+            if r0 % mi != ri {
+                return (0, 0);
+            }
+            continue;
+        }
+        // see `crt` for the bounds of the intermediate values
+        let (g, im) = internal_math::inv_gcd_i128(m0, mi);
+        let u1 = mi / g;
+        if (ri - r0) % g != 0 {
+            return (0, 0);
+        }
+        let x = (ri - r0) / g % u1 * im % u1;
+        r0 += x * m0;
+        m0 *= u1; // -> lcm(m0, mi)
+        if r0 < 0 {
+            r0 += m0
+        };
+    }
+
+    (r0, m0)
+}
+
+/// Returns $x \bmod \mathrm{target\\_mod}$, where $x$ is the unique solution of $x \equiv r_i \pmod{m_i}$ in $\left[0, \prod m \right)$.
+///
+/// This is the Garner's algorithm. Unlike [`crt`], $x$ itself is never materialised, so $\prod m$ can be arbitrarily large.
+///
+/// If $n = 0$, it returns $0 \bmod \mathrm{target\\_mod}$.
+///
+/// # Constraints
+///
+/// - $|r| = |m|$
+/// - $1 \leq m_{\forall i}$
+/// - $m$ are pairwise coprime
+/// - $1 \leq \mathrm{target\\_mod}$
+///
+/// # Panics
+///
+/// Panics if the above constraints are not satisfied.
+///
+/// # Complexity
+///
+/// - $O(n^2 + n \log \max(m))$
+///
+/// # Example
+///
+/// ```
+/// use ac_library::math;
+///
+/// let r = [2, 3, 2];
+/// let m = [3, 5, 7];
+/// assert_eq!(math::garner(&r, &m, 10), 3);
+/// ```
+///
+/// [`crt`]: ./fn.crt.html
+pub fn garner(r: &[i64], m: &[i64], target_mod: i64) -> i64 {
+    assert_eq!(r.len(), m.len());
+    assert!(1 <= target_mod);
+    let mul = |a: i64, b: i64, m: i64| (a as i128 * b as i128 % m as i128) as i64;
+    let mods = m
+        .iter()
+        .copied()
+        .chain(std::iter::once(target_mod))
+        .collect::<Vec<_>>();
+    // Contracts: x = constants[i] + coeffs[i] * (the undetermined part) (mod mods[i])
+    let mut coeffs = vec![1; mods.len()];
+    let mut constants = vec![0; mods.len()];
+    for (k, (&rk, &mk)) in r.iter().zip(m).enumerate() {
+        assert!(1 <= mk);
+        let d = internal_math::safe_mod(internal_math::safe_mod(rk, mk) - constants[k], mk);
+        let t = mul(d, inv_mod(coeffs[k], mk), mk);
+        for ((c, d), &mi) in coeffs[k + 1..]
+            .iter_mut()
+            .zip(&mut constants[k + 1..])
+            .zip(&mods[k + 1..])
+        {
+            *d = ((*d as i128 + t as i128 * *c as i128) % mi as i128) as i64;
+            *c = mul(*c, mk, mi);
+        }
+    }
+    constants[r.len()] % target_mod
+}
+
 /// Returns
 ///
 /// $$\sum_{i = 0}^{n - 1} \left\lfloor \frac{a \times i + b}{m} \right\rfloor.$$
@@ -209,28 +664,1191 @@ pub fn floor_sum(n: i64, m: i64, a: i64, b: i64) -> i64 {
     ret.0 as i64
 }
 
-#[cfg(test)]
-mod tests {
-    #![allow(clippy::unreadable_literal)]
-    #![allow(clippy::cognitive_complexity)]
-    use super::*;
-    #[test]
-    fn test_pow_mod() {
-        assert_eq!(pow_mod(0, 0, 1), 0);
-        assert_eq!(pow_mod(0, 0, 3), 1);
-        assert_eq!(pow_mod(0, 0, 723), 1);
-        assert_eq!(pow_mod(0, 0, 998244353), 1);
-        assert_eq!(pow_mod(0, 0, 2u32.pow(31)), 1);
-
-        assert_eq!(pow_mod(0, 1, 1), 0);
-        assert_eq!(pow_mod(0, 1, 3), 0);
-        assert_eq!(pow_mod(0, 1, 723), 0);
-        assert_eq!(pow_mod(0, 1, 998244353), 0);
-        assert_eq!(pow_mod(0, 1, 2u32.pow(31)), 0);
-
-        assert_eq!(pow_mod(0, i64::MAX, 1), 0);
-        assert_eq!(pow_mod(0, i64::MAX, 3), 0);
-        assert_eq!(pow_mod(0, i64::MAX, 723), 0);
+/// Returns whether $n$ is a prime number.
+///
+/// Performs the deterministic Miller–Rabin test with the bases $2, 325, 9375, 28178, 450775, 9780504, 1795265022$, which is correct for all $n < 2^{64}$.
+///
+/// # Complexity
+///
+/// - $O(\log n)$
+///
+/// # Example
+///
+/// ```
+/// use ac_library::math;
+///
+/// assert!(math::is_prime_u64(998_244_353));
+/// assert!(math::is_prime_u64(1_000_000_000_000_000_003));
+/// assert!(!math::is_prime_u64(1_000_000_007 * 998_244_353));
+/// ```
+pub fn is_prime_u64(n: u64) -> bool {
+    internal_math::is_prime_u64(n)
+}
+
+/// Returns the prime factorization of $n$.
+///
+/// Returns the pairs $(p, e)$ of a prime factor $p$ and its exponent $e$, in ascending order of $p$.
+/// If $n = 1$, it returns an empty `Vec`.
+///
+/// It uses Pollard's rho algorithm with Brent's cycle detection.
+///
+/// # Constraints
+///
+/// - $1 \leq n$
+///
+/// # Panics
+///
+/// Panics if the above constraints are not satisfied.
+///
+/// # Complexity
+///
+/// - $O(n^{1/4} \log n)$ expected
+///
+/// # Example
+///
+/// ```
+/// use ac_library::math;
+///
+/// assert_eq!(math::factorize(360), [(2, 3), (3, 2), (5, 1)]);
+/// assert_eq!(
+///     math::factorize(1_000_000_007 * 998_244_353),
+///     [(998_244_353, 1), (1_000_000_007, 1)],
+/// );
+/// ```
+pub fn factorize(mut n: u64) -> Vec<(u64, u32)> {
+    assert!(1 <= n);
+    let mut primes = vec![];
+    for p in 2..100 {
+        while n % p == 0 {
+            primes.push(p);
+            n /= p;
+        }
+    }
+    let mut stack = vec![n];
+    while let Some(n) = stack.pop() {
+        if n == 1 {
+            continue;
+        }
+        if internal_math::is_prime_u64(n) {
+            primes.push(n);
+            continue;
+        }
+        let d = pollard_rho(n);
+        stack.push(d);
+        stack.push(n / d);
+    }
+    primes.sort_unstable();
+    let mut ret: Vec<(u64, u32)> = vec![];
+    for p in primes {
+        match ret.last_mut() {
+            Some((q, e)) if *q == p => *e += 1,
+            _ => ret.push((p, 1)),
+        }
+    }
+    ret
+}
+
+/// Returns all the positive divisors of $n$ in ascending order.
+///
+/// # Constraints
+///
+/// - $1 \leq n$
+///
+/// # Panics
+///
+/// Panics if the above constraints are not satisfied.
+///
+/// # Complexity
+///
+/// - $O(n^{1/4} \log n + d(n) \log d(n))$ expected, where $d(n)$ is the number of the divisors.
+///
+/// # Example
+///
+/// ```
+/// use ac_library::math;
+///
+/// assert_eq!(math::divisors(12), [1, 2, 3, 4, 6, 12]);
+/// ```
+pub fn divisors(n: u64) -> Vec<u64> {
+    let mut ret = vec![1];
+    for (p, e) in factorize(n) {
+        let len = ret.len();
+        let mut pk = 1;
+        for _ in 0..e {
+            pk *= p;
+            for i in 0..len {
+                ret.push(ret[i] * pk);
+            }
+        }
+    }
+    ret.sort_unstable();
+    ret
+}
+
+/// A linear sieve of Eratosthenes over $[0, n]$.
+///
+/// It stores the smallest prime factor of each integer, which makes it possible to factorize any integer in $[1, n]$ in $O(\log n)$ time.
+/// It also computes tables of some multiplicative functions.
+///
+/// # Example
+///
+/// ```
+/// use ac_library::math::Sieve;
+///
+/// let sieve = Sieve::new(100);
+/// assert_eq!(&sieve.primes()[..5], [2, 3, 5, 7, 11]);
+/// assert!(sieve.is_prime(97));
+/// assert_eq!(sieve.smallest_prime_factor(91), 7);
+/// assert_eq!(sieve.factorize(60), [(2, 2), (3, 1), (5, 1)]);
+/// assert_eq!(sieve.totient_table()[36], 12);
+/// assert_eq!(sieve.mobius_table()[30], -1);
+/// assert_eq!(sieve.divisor_count_table()[36], 9);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Sieve {
+    // spf[i] = the smallest prime factor of i (0 for i < 2)
+    spf: Vec<u32>,
+    primes: Vec<usize>,
+}
+
+impl Sieve {
+    /// Creates a new `Sieve` over $[0, n]$.
+    ///
+    /// # Constraints
+    ///
+    /// - $n < 2^{32}$
+    ///
+    /// # Panics
+    ///
+    /// Panics if the above constraints are not satisfied.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(n)$
+    pub fn new(n: usize) -> Self {
+        assert!((n as u64) < 1 << 32);
+        let mut spf = vec![0; n + 1];
+        let mut primes = vec![];
+        for i in 2..=n {
+            if spf[i] == 0 {
+                spf[i] = i as u32;
+                primes.push(i);
+            }
+            for &p in &primes {
+                if p > spf[i] as usize || i * p > n {
+                    break;
+                }
+                spf[i * p] = p as u32;
+            }
+        }
+        Self { spf, primes }
+    }
+
+    /// Returns $n$.
+    pub fn max(&self) -> usize {
+        self.spf.len() - 1
+    }
+
+    /// Returns the prime numbers not greater than $n$ in ascending order.
+    pub fn primes(&self) -> &[usize] {
+        &self.primes
+    }
+
+    /// Returns whether $x$ is a prime number.
+    ///
+    /// # Constraints
+    ///
+    /// - $0 \leq x \leq n$
+    ///
+    /// # Panics
+    ///
+    /// Panics if the above constraints are not satisfied.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(1)$
+    pub fn is_prime(&self, x: usize) -> bool {
+        x >= 2 && self.spf[x] as usize == x
+    }
+
+    /// Returns the smallest prime factor of $x$.
+    ///
+    /// # Constraints
+    ///
+    /// - $2 \leq x \leq n$
+    ///
+    /// # Panics
+    ///
+    /// Panics if the above constraints are not satisfied.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(1)$
+    pub fn smallest_prime_factor(&self, x: usize) -> usize {
+        assert!(2 <= x);
+        self.spf[x] as usize
+    }
+
+    /// Returns the prime factorization of $x$ as the pairs $(p, e)$ in ascending order of $p$.
+    ///
+    /// # Constraints
+    ///
+    /// - $1 \leq x \leq n$
+    ///
+    /// # Panics
+    ///
+    /// Panics if the above constraints are not satisfied.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(\log x)$
+    pub fn factorize(&self, mut x: usize) -> Vec<(usize, u32)> {
+        assert!(1 <= x && x <= self.max());
+        let mut ret: Vec<(usize, u32)> = vec![];
+        while x > 1 {
+            let p = self.spf[x] as usize;
+            match ret.last_mut() {
+                Some((q, e)) if *q == p => *e += 1,
+                _ => ret.push((p, 1)),
+            }
+            x /= p;
+        }
+        ret
+    }
+
+    /// Returns Euler's totient function $\varphi(0), \varphi(1), \ldots, \varphi(n)$, where $\varphi(0) = 0$.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(n)$
+    pub fn totient_table(&self) -> Vec<usize> {
+        let n = self.max();
+        let mut phi = vec![0; n + 1];
+        if n >= 1 {
+            phi[1] = 1;
+        }
+        for i in 2..=n {
+            let p = self.spf[i] as usize;
+            let j = i / p;
+            phi[i] = if j % p == 0 {
+                phi[j] * p
+            } else {
+                phi[j] * (p - 1)
+            };
+        }
+        phi
+    }
+
+    /// Returns the Möbius function $\mu(0), \mu(1), \ldots, \mu(n)$, where $\mu(0) = 0$.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(n)$
+    pub fn mobius_table(&self) -> Vec<i32> {
+        let n = self.max();
+        let mut mu = vec![0; n + 1];
+        if n >= 1 {
+            mu[1] = 1;
+        }
+        for i in 2..=n {
+            let p = self.spf[i] as usize;
+            let j = i / p;
+            mu[i] = if j % p == 0 { 0 } else { -mu[j] };
+        }
+        mu
+    }
+
+    /// Returns the number of the positive divisors $d(0), d(1), \ldots, d(n)$, where $d(0) = 0$.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(n)$
+    pub fn divisor_count_table(&self) -> Vec<u32> {
+        let n = self.max();
+        let mut d = vec![0; n + 1];
+        // the exponent of the smallest prime factor
+        let mut e = vec![0; n + 1];
+        if n >= 1 {
+            d[1] = 1;
+        }
+        for i in 2..=n {
+            let p = self.spf[i] as usize;
+            let j = i / p;
+            if j % p == 0 {
+                e[i] = e[j] + 1;
+                d[i] = d[j] / (e[j] + 1) * (e[i] + 1);
+            } else {
+                e[i] = 1;
+                d[i] = d[j] * 2;
+            }
+        }
+        d
+    }
+}
+
+/// Returns the prime numbers in $[l, r)$ in ascending order, by the segmented sieve of Eratosthenes.
+///
+/// # Constraints
+///
+/// - $l \leq r$
+///
+/// # Panics
+///
+/// Panics if the above constraints are not satisfied.
+///
+/// # Complexity
+///
+/// - $O((r - l) \log \log r + \sqrt r)$ time
+/// - $O(r - l + \sqrt r)$ space
+///
+/// It is practical for $r$ up to about $10^{14}$ because of the $O(\sqrt r)$ table.
+///
+/// # Example
+///
+/// ```
+/// use ac_library::math;
+///
+/// assert_eq!(
+///     math::segmented_sieve(1_000_000_000_000, 1_000_000_000_100),
+///     [1_000_000_000_039, 1_000_000_000_061, 1_000_000_000_063, 1_000_000_000_091],
+/// );
+/// ```
+pub fn segmented_sieve(l: u64, r: u64) -> Vec<u64> {
+    assert!(l <= r);
+    let l = l.max(2);
+    if l >= r {
+        return vec![];
+    }
+    // every composite number in [l, r) has a prime factor <= sqrt
+    let sqrt = iroot(r - 1, 2);
+    let mut is_prime = vec![true; (r - l) as usize];
+    let mut small = vec![true; sqrt as usize + 1];
+    for p in 2..=sqrt {
+        if !small[p as usize] {
+            continue;
+        }
+        for q in (p * p..=sqrt).step_by(p as usize) {
+            small[q as usize] = false;
+        }
+        // the smallest multiple of p not less than l, which may exceed u64::MAX
+        let start = match ((l - 1) / p + 1).checked_mul(p) {
+            Some(x) => std::cmp::max(p * p, x),
+            None => continue,
+        };
+        for x in (start..r).step_by(p as usize) {
+            is_prime[(x - l) as usize] = false;
+        }
+    }
+    is_prime
+        .iter()
+        .enumerate()
+        .filter(|&(_, &b)| b)
+        .map(|(i, _)| l + i as u64)
+        .collect()
+}
+
+/// Returns the number of the prime numbers not greater than $n$, i.e. $\pi(n)$.
+///
+/// # Complexity
+///
+/// - $O(n^{3/4} / \log n)$
+///
+/// # Example
+///
+/// ```
+/// use ac_library::math;
+///
+/// assert_eq!(math::prime_count(100), 25);
+/// assert_eq!(math::prime_count(1_000_000_000), 50_847_534);
+/// ```
+pub fn prime_count(n: u64) -> u64 {
+    prime_sum_table(n, |_| 1, |v| v - 1)
+        .last()
+        .copied()
+        .unwrap_or(0)
+}
+
+/// Calculates $\sum_{p \leq v} f(p)$ for all the distinct values $v$ of $\lfloor n / i \rfloor$, by the Lucy–Hedgehog algorithm.
+///
+/// $f$ must be completely multiplicative, and `prefix(v)` must return $\sum_{i = 2}^v f(i)$.
+/// Non-completely multiplicative functions on the primes, such as $\varphi(p) = p - 1$, can be handled by combining the tables elementwise.
+///
+/// The $j$-th element of the result corresponds to the $j$-th smallest value of $\lfloor n / i \rfloor\ (1 \leq i \leq n)$.
+/// Therefore the last element is $\sum_{p \leq n} f(p)$.
+/// If $n = 0$, it returns an empty `Vec`.
+///
+/// # Complexity
+///
+/// - $O(n^{3/4} / \log n)$ calls of `f` and arithmetic operations on `T`
+///
+/// # Example
+///
+/// ```
+/// use ac_library::math;
+///
+/// // the sums of the primes not greater than 1, 2, 3, 5 and 10
+/// assert_eq!(
+///     math::prime_sum_table(10, |p| p, |v| v * (v + 1) / 2 - 1),
+///     [0, 2, 5, 10, 17],
+/// );
+/// ```
+pub fn prime_sum_table<T, F, G>(n: u64, f: F, prefix: G) -> Vec<T>
+where
+    T: Copy + Add<Output = T> + Sub<Output = T> + Mul<Output = T>,
+    F: Fn(u64) -> T,
+    G: Fn(u64) -> T,
+{
+    let qs = QuotientIndex::new(n);
+    let mut s = qs.values.iter().map(|&v| prefix(v)).collect::<Vec<_>>();
+    // s[i] = \sum_{2 <= x <= v_i, x is a prime or spf(x) > p} f(x) after the step for p
+    for &p in Sieve::new(qs.sqrt as usize).primes() {
+        let p = p as u64;
+        let fp = f(p);
+        let below = s[qs.index(p - 1)];
+        for i in (0..qs.values.len()).rev() {
+            let v = qs.values[i];
+            if v / p < p {
+                break;
+            }
+            s[i] = s[i] - fp * (s[qs.index(v / p)] - below);
+        }
+    }
+    s
+}
+
+/// Returns $\sum_{i = 1}^n F(i)$ for a multiplicative function $F$, by the Min_25 sieve.
+///
+/// `prime_sums` must be $\sum_{p \leq v} F(p)$ for the distinct values $v$ of $\lfloor n / i \rfloor$, in the same form as the result of [`prime_sum_table`].
+/// `f(p, e)` must return $F(p^e)$.
+///
+/// # Constraints
+///
+/// - $|$`prime_sums`$|$ is the number of the distinct values of $\lfloor n / i \rfloor$.
+///
+/// # Panics
+///
+/// Panics if the above constraints are not satisfied.
+///
+/// # Complexity
+///
+/// - $O(n^{3/4} / \log n)$ calls of `f` and arithmetic operations on `T`, in practice
+///
+/// # Example
+///
+/// ```
+/// use ac_library::math;
+///
+/// // \sum_{i <= n} \varphi(i)
+/// let n = 100;
+/// let primes = math::prime_sum_table(n, |_| 1i64, |v| v as i64 - 1);
+/// let sums = math::prime_sum_table(n, |p| p as i64, |v| (v * (v + 1) / 2) as i64 - 1);
+/// let phi = sums
+///     .iter()
+///     .zip(&primes)
+///     .map(|(s, c)| s - c)
+///     .collect::<Vec<_>>();
+/// let totient = |p: u64, e: u32| ((p - 1) * p.pow(e - 1)) as i64;
+/// assert_eq!(math::multiplicative_prefix_sum(n, &phi, totient), 3044);
+/// ```
+///
+/// [`prime_sum_table`]: ./fn.prime_sum_table.html
+pub fn multiplicative_prefix_sum<T, F>(n: u64, prime_sums: &[T], f: F) -> T
+where
+    T: Copy + Add<Output = T> + Sub<Output = T> + Mul<Output = T> + From<u8>,
+    F: Fn(u64, u32) -> T,
+{
+    let qs = QuotientIndex::new(n);
+    assert_eq!(prime_sums.len(), qs.values.len());
+    if n == 0 {
+        return T::from(0);
+    }
+    let primes = Sieve::new(qs.sqrt as usize)
+        .primes()
+        .iter()
+        .map(|&p| p as u64)
+        .collect::<Vec<_>>();
+
+    // \sum_{2 <= x <= v, spf(x) >= primes[j]} F(x)
+    fn rec<T, F>(v: u64, j: usize, primes: &[u64], qs: &QuotientIndex, g: &[T], f: &F) -> T
+    where
+        T: Copy + Add<Output = T> + Sub<Output = T> + Mul<Output = T> + From<u8>,
+        F: Fn(u64, u32) -> T,
+    {
+        let lower = if j == 0 {
+            T::from(0)
+        } else {
+            g[qs.index(primes[j - 1])]
+        };
+        if j > 0 && v <= primes[j - 1] {
+            return T::from(0);
+        }
+        let mut ret = g[qs.index(v)] - lower;
+        for (k, &p) in primes.iter().enumerate().skip(j) {
+            if p > v / p {
+                break;
+            }
+            let (mut pe, mut e) = (p, 1);
+            while pe <= v / p {
+                ret = ret + f(p, e) * rec(v / pe, k + 1, primes, qs, g, f) + f(p, e + 1);
+                pe *= p;
+                e += 1;
+            }
+        }
+        ret
+    }
+
+    T::from(1) + rec(n, 0, &primes, &qs, prime_sums, &f)
+}
+
+/// The distinct values of $\lfloor n / i \rfloor$ in ascending order, and the inverse lookup.
+struct QuotientIndex {
+    n: u64,
+    sqrt: u64,
+    values: Vec<u64>,
+}
+
+impl QuotientIndex {
+    fn new(n: u64) -> Self {
+        let mut values = quotient_ranges(n).map(|(q, _)| q).collect::<Vec<_>>();
+        values.reverse();
+        // the values not greater than sqrt(n) are exactly 1, 2, ..., floor(sqrt(n))
+        let sqrt = values.partition_point(|&v| v <= n / v) as u64;
+        Self { n, sqrt, values }
+    }
+
+    /// `v` must be one of `values`.
+    fn index(&self, v: u64) -> usize {
+        if v <= self.sqrt {
+            v as usize - 1
+        } else {
+            self.values.len() - (self.n / v) as usize
+        }
+    }
+}
+
+/// Returns an iterator over the blocks of $i$ on which $\lfloor n / i \rfloor$ is constant.
+///
+/// It yields $(q, l..r)$ for $i = 1, 2, \ldots, n$ in ascending order of $i$, where $\lfloor n / i \rfloor = q$ for all $i \in [l, r)$.
+/// There are $O(\sqrt{n})$ blocks.
+///
+/// # Constraints
+///
+/// - $n < 2^{64} - 1$
+///
+/// # Panics
+///
+/// Panics if the above constraints are not satisfied.
+///
+/// # Complexity
+///
+/// - $O(1)$ per block
+///
+/// # Example
+///
+/// ```
+/// use ac_library::math;
+///
+/// let blocks = math::quotient_ranges(10).collect::<Vec<_>>();
+/// assert_eq!(blocks, [(10, 1..2), (5, 2..3), (3, 3..4), (2, 4..6), (1, 6..11)]);
+///
+/// // \sum_{i = 1}^{n} \lfloor n / i \rfloor
+/// let sum = math::quotient_ranges(10).map(|(q, r)| q * (r.end - r.start)).sum::<u64>();
+/// assert_eq!(sum, 27);
+/// ```
+pub fn quotient_ranges(n: u64) -> QuotientRanges {
+    assert!(n < u64::MAX);
+    QuotientRanges {
+        n,
+        l: 1,
+        ceil: false,
+    }
+}
+
+/// Returns an iterator over the blocks of $i$ on which $\lceil n / i \rceil$ is constant.
+///
+/// It yields $(q, l..r)$ for $i = 1, 2, \ldots, n$ in ascending order of $i$, where $\lceil n / i \rceil = q$ for all $i \in [l, r)$.
+/// There are $O(\sqrt{n})$ blocks.
+///
+/// # Constraints
+///
+/// - $n < 2^{64} - 1$
+///
+/// # Panics
+///
+/// Panics if the above constraints are not satisfied.
+///
+/// # Complexity
+///
+/// - $O(1)$ per block
+///
+/// # Example
+///
+/// ```
+/// use ac_library::math;
+///
+/// let blocks = math::ceil_quotient_ranges(10).collect::<Vec<_>>();
+/// assert_eq!(blocks, [(10, 1..2), (5, 2..3), (4, 3..4), (3, 4..5), (2, 5..10), (1, 10..11)]);
+/// ```
+pub fn ceil_quotient_ranges(n: u64) -> QuotientRanges {
+    assert!(n < u64::MAX);
+    QuotientRanges {
+        n,
+        l: 1,
+        ceil: true,
+    }
+}
+
+/// An iterator returned by [`quotient_ranges`] and [`ceil_quotient_ranges`].
+///
+/// [`quotient_ranges`]: ./fn.quotient_ranges.html
+/// [`ceil_quotient_ranges`]: ./fn.ceil_quotient_ranges.html
+#[derive(Clone, Debug)]
+pub struct QuotientRanges {
+    n: u64,
+    l: u64,
+    ceil: bool,
+}
+
+impl Iterator for QuotientRanges {
+    type Item = (u64, Range<u64>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (n, l) = (self.n, self.l);
+        if l > n {
+            return None;
+        }
+        let (q, r) = if !self.ceil {
+            (n / l, n / (n / l) + 1)
+        } else if l == n {
+            (1, n + 1)
+        } else {
+            // ceil(n / i) = floor((n - 1) / i) + 1
+            let q = (n - 1) / l;
+            (q + 1, (n - 1) / q + 1)
+        };
+        self.l = r;
+        Some((q, l..r))
+    }
+}
+
+/// Finds a non-trivial divisor of a composite number $n$ which has no prime factor less than $100$.
+///
+/// Reference: R. P. Brent, An improved Monte Carlo factorization algorithm
+#[allow(clippy::many_single_char_names)]
+fn pollard_rho(n: u64) -> u64 {
+    const BLOCK: u64 = 128;
+    let mul = |a, b| internal_math::mul_mod_u64(a, b, n);
+    let gcd = |mut a: u64, mut b: u64| {
+        while b != 0 {
+            a %= b;
+            swap(&mut a, &mut b);
+        }
+        a
+    };
+    for c in 1.. {
+        let f = |x| ((mul(x, x) as u128 + c as u128) % n as u128) as u64;
+        let (mut x, mut y, mut ys) = (0, 2, 0);
+        let mut q = 1;
+        let mut g = 1;
+        let mut r = 1;
+        while g == 1 {
+            x = y;
+            for _ in 0..r {
+                y = f(y);
+            }
+            let mut k = 0;
+            while k < r && g == 1 {
+                ys = y;
+                for _ in 0..BLOCK.min(r - k) {
+                    y = f(y);
+                    q = mul(q, x.max(y) - x.min(y));
+                }
+                g = gcd(q, n);
+                k += BLOCK;
+            }
+            r *= 2;
+        }
+        if g == n {
+            // The product of a block became 0, so retry the block one step at a time.
+            loop {
+                ys = f(ys);
+                g = gcd(x.max(ys) - x.min(ys), n);
+                if g != 1 {
+                    break;
+                }
+            }
+        }
+        if g != n {
+            return g;
+        }
+    }
+    unreachable!()
+}
+
+/// Continued fractions and the Stern–Brocot tree.
+///
+/// A rational number $p / q$ is represented as the pair `(p, q)`, which need not be reduced unless otherwise noted.
+/// The returned pairs are always reduced.
+///
+/// A node of the Stern–Brocot tree is identified by the path from the root $1 / 1$, encoded as the run lengths of alternating moves: `path[0]` moves to the right, `path[1]` to the left, `path[2]` to the right, and so on.
+/// For example, $3 / 5$ is reached by one move to the left, one to the right and one to the left from the root, which is encoded as `[0, 1, 1, 1]`.
+/// The path of the root is `[]`, and the last element of a path is never $0$.
+///
+/// All the functions are generic over primitive integer types.
+/// The products of two numerators or denominators must fit in `T`; use `i128` or `u128` for values beyond $2^{32}$.
+pub mod rational {
+    use crate::internal_type_traits::Integral;
+
+    /// Returns the continued fraction expansion $[a_0; a_1, \ldots, a_k]$ of $p / q$.
+    ///
+    /// If $p \neq 0$, $a_k \geq 2$ holds unless $k = 0$.
+    ///
+    /// # Constraints
+    ///
+    /// - $0 \leq p$
+    /// - $1 \leq q$
+    ///
+    /// # Panics
+    ///
+    /// Panics if the above constraints are not satisfied.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(\log q)$
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ac_library::math::rational;
+    ///
+    /// assert_eq!(rational::continued_fraction(415, 93), [4, 2, 6, 7]);
+    /// assert_eq!(rational::from_continued_fraction(&[4, 2, 6, 7]), (415, 93));
+    /// ```
+    pub fn continued_fraction<T: Integral>(mut p: T, mut q: T) -> Vec<T> {
+        assert!(T::zero() <= p && T::one() <= q);
+        let mut a = vec![];
+        while q != T::zero() {
+            a.push(p / q);
+            p %= q;
+            std::mem::swap(&mut p, &mut q);
+        }
+        a
+    }
+
+    /// Returns the value of the continued fraction $[a_0; a_1, \ldots, a_k]$ as a reduced fraction.
+    ///
+    /// If $a$ is empty, it returns $(1, 0)$.
+    ///
+    /// # Constraints
+    ///
+    /// - $1 \leq a_i$ for $i \geq 1$
+    ///
+    /// # Complexity
+    ///
+    /// - $O(k)$
+    pub fn from_continued_fraction<T: Integral>(a: &[T]) -> (T, T) {
+        // (p, q) and (pp, qq) are the last two convergents
+        let (mut p, mut q) = (T::one(), T::zero());
+        let (mut pp, mut qq) = (T::zero(), T::one());
+        for &ai in a {
+            let (np, nq) = (ai * p + pp, ai * q + qq);
+            pp = p;
+            qq = q;
+            p = np;
+            q = nq;
+        }
+        (p, q)
+    }
+
+    /// Returns the path from the root to $p / q$ in the Stern–Brocot tree.
+    ///
+    /// # Constraints
+    ///
+    /// - $1 \leq p, q$
+    ///
+    /// # Panics
+    ///
+    /// Panics if the above constraints are not satisfied.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(\log \min(p, q))$
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ac_library::math::rational;
+    ///
+    /// assert_eq!(rational::path(3, 5), [0, 1, 1, 1]);
+    /// assert_eq!(rational::path(4, 1), [3]);
+    /// assert_eq!(rational::path(2, 2), []);
+    /// ```
+    pub fn path<T: Integral>(p: T, q: T) -> Vec<T> {
+        assert!(T::one() <= p && T::one() <= q);
+        let mut a = continued_fraction(p, q);
+        *a.last_mut().unwrap() -= T::one();
+        if a.last() == Some(&T::zero()) {
+            a.pop();
+        }
+        a
+    }
+
+    /// Returns the node of the Stern–Brocot tree reached by `path`.
+    ///
+    /// Unlike [`path`], zeros are allowed anywhere in `path`.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(|\mathrm{path}|)$
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ac_library::math::rational;
+    ///
+    /// assert_eq!(rational::from_path(&[0, 1, 1, 1]), (3, 5));
+    /// ```
+    ///
+    /// [`path`]: ./fn.path.html
+    pub fn from_path<T: Integral>(path: &[T]) -> (T, T) {
+        let (l, r) = subtree_bounds(path);
+        (l.0 + r.0, l.1 + r.1)
+    }
+
+    /// Returns the depth of $p / q$ in the Stern–Brocot tree, where the root $1 / 1$ has depth $0$.
+    ///
+    /// # Constraints
+    ///
+    /// - $1 \leq p, q$
+    ///
+    /// # Panics
+    ///
+    /// Panics if the above constraints are not satisfied.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(\log \min(p, q))$
+    pub fn depth<T: Integral>(p: T, q: T) -> T {
+        path(p, q).into_iter().sum()
+    }
+
+    /// Returns the ancestor of $p / q$ at depth $d$ in the Stern–Brocot tree, or `None` if $d$ is greater than the depth of $p / q$.
+    ///
+    /// # Constraints
+    ///
+    /// - $1 \leq p, q$
+    /// - $0 \leq d$
+    ///
+    /// # Panics
+    ///
+    /// Panics if the above constraints are not satisfied.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(\log \min(p, q))$
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ac_library::math::rational;
+    ///
+    /// assert_eq!(rational::ancestor(3, 5, 0), Some((1, 1)));
+    /// assert_eq!(rational::ancestor(3, 5, 2), Some((2, 3)));
+    /// assert_eq!(rational::ancestor(3, 5, 3), Some((3, 5)));
+    /// assert_eq!(rational::ancestor(3, 5, 4), None);
+    /// ```
+    pub fn ancestor<T: Integral>(p: T, q: T, mut d: T) -> Option<(T, T)> {
+        assert!(T::zero() <= d);
+        let mut path = path(p, q);
+        for i in 0..path.len() {
+            if d <= path[i] {
+                path[i] = d;
+                path.truncate(i + 1);
+                return Some(from_path(&path));
+            }
+            d -= path[i];
+        }
+        if d == T::zero() {
+            Some(from_path(&path))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the lowest common ancestor of $p_1 / q_1$ and $p_2 / q_2$ in the Stern–Brocot tree.
+    ///
+    /// # Constraints
+    ///
+    /// - $1 \leq p_1, q_1, p_2, q_2$
+    ///
+    /// # Panics
+    ///
+    /// Panics if the above constraints are not satisfied.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(\log \min(p_1, q_1) + \log \min(p_2, q_2))$
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ac_library::math::rational;
+    ///
+    /// assert_eq!(rational::lca((3, 5), (3, 4)), (2, 3));
+    /// assert_eq!(rational::lca((3, 5), (1, 2)), (1, 2));
+    /// ```
+    pub fn lca<T: Integral>(x: (T, T), y: (T, T)) -> (T, T) {
+        let (a, b) = (path(x.0, x.1), path(y.0, y.1));
+        let mut common = vec![];
+        for (&ai, &bi) in a.iter().zip(&b) {
+            common.push(ai.min(bi));
+            if ai != bi {
+                break;
+            }
+        }
+        from_path(&common)
+    }
+
+    /// Returns the bounds $(l, r)$ of the subtree of the node reached by `path`.
+    ///
+    /// The subtree consists of all the rationals in the open interval $(l, r)$, where $r$ may be $1 / 0 = \infty$.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(|\mathrm{path}|)$
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ac_library::math::rational;
+    ///
+    /// assert_eq!(rational::subtree_bounds(&[]), ((0, 1), (1, 0)));
+    /// assert_eq!(rational::subtree_bounds(&[0, 1, 1, 1]), ((1, 2), (2, 3)));
+    /// ```
+    pub fn subtree_bounds<T: Integral>(path: &[T]) -> ((T, T), (T, T)) {
+        let (mut l, mut r) = ((T::zero(), T::one()), (T::one(), T::zero()));
+        for (i, &k) in path.iter().enumerate() {
+            if i % 2 == 0 {
+                l = (l.0 + k * r.0, l.1 + k * r.1);
+            } else {
+                r = (r.0 + k * l.0, r.1 + k * l.1);
+            }
+        }
+        (l, r)
+    }
+
+    /// Returns the rational number in the closed interval $[\mathrm{lo}, \mathrm{hi}]$ with the smallest denominator.
+    ///
+    /// If there are several, the one with the smallest numerator is returned.
+    ///
+    /// # Constraints
+    ///
+    /// - $0 \leq \mathrm{lo} \leq \mathrm{hi}$
+    /// - $1 \leq$ the denominators of $\mathrm{lo}$ and $\mathrm{hi}$
+    ///
+    /// # Panics
+    ///
+    /// Panics if the above constraints are not satisfied.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(\log \max(\mathrm{lo}_q, \mathrm{hi}_q))$
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ac_library::math::rational;
+    ///
+    /// // 3.14 <= 22 / 7 <= 3.15
+    /// assert_eq!(rational::best_rational_in_range((314i64, 100), (315, 100)), (22, 7));
+    /// assert_eq!(rational::best_rational_in_range((3i64, 2), (7, 2)), (2, 1));
+    /// ```
+    pub fn best_rational_in_range<T: Integral>(lo: (T, T), hi: (T, T)) -> (T, T) {
+        let (zero, one) = (T::zero(), T::one());
+        assert!(zero <= lo.0 && one <= lo.1 && one <= hi.1);
+        assert!(lo.0 * hi.1 <= hi.0 * lo.1);
+        if lo.0 == zero {
+            return (zero, one);
+        }
+        // Contracts: l < lo <= hi < r
+        let (mut l, mut r) = ((zero, one), (one, zero));
+        loop {
+            let m = (l.0 + r.0, l.1 + r.1);
+            if m.0 * lo.1 < lo.0 * m.1 {
+                // m < lo: l += k * r with the largest k such that l + k * r < lo
+                let k = (lo.0 * l.1 - l.0 * lo.1 - one) / (r.0 * lo.1 - lo.0 * r.1);
+                l = (l.0 + k * r.0, l.1 + k * r.1);
+            } else if m.0 * hi.1 > hi.0 * m.1 {
+                // m > hi: r += k * l with the largest k such that r + k * l > hi
+                let k = (r.0 * hi.1 - hi.0 * r.1 - one) / (hi.0 * l.1 - l.0 * hi.1);
+                r = (r.0 + k * l.0, r.1 + k * l.1);
+            } else {
+                return m;
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn gcd(a: i64, b: i64) -> i64 {
+            if b == 0 {
+                a
+            } else {
+                gcd(b, a % b)
+            }
+        }
+
+        #[test]
+        fn test_continued_fraction() {
+            for p in 0..60i64 {
+                for q in 1..60i64 {
+                    let a = continued_fraction(p, q);
+                    let g = gcd(p, q);
+                    assert_eq!(from_continued_fraction(&a), (p / g, q / g));
+                    assert!(a[1..].iter().all(|&x| x >= 1));
+                    if p != 0 && a.len() > 1 {
+                        assert!(*a.last().unwrap() >= 2);
+                    }
+                }
+            }
+            assert_eq!(continued_fraction(0, 5), [0]);
+            assert_eq!(from_continued_fraction::<i32>(&[]), (1, 0));
+            let a = continued_fraction(u64::MAX, u64::MAX - 1);
+            assert_eq!(a, [1, u64::MAX - 1]);
+        }
+
+        #[test]
+        fn test_stern_brocot() {
+            // BFS over the tree up to depth 10
+            let mut nodes = vec![(vec![], (1i64, 1i64), (0i64, 1i64), (1i64, 0i64))];
+            for _ in 0..10 {
+                let mut next = vec![];
+                for (dirs, m, l, r) in nodes {
+                    let mut encoded = vec![];
+                    let mut last = 'R';
+                    for &d in &dirs {
+                        if encoded.is_empty() || d != last {
+                            if encoded.is_empty() && d == 'L' {
+                                encoded.push(0);
+                            }
+                            encoded.push(0);
+                            last = d;
+                        }
+                        *encoded.last_mut().unwrap() += 1;
+                    }
+                    assert_eq!(path(m.0, m.1), encoded);
+                    assert_eq!(path(3 * m.0, 3 * m.1), encoded);
+                    assert_eq!(from_path(&encoded), m);
+                    assert_eq!(subtree_bounds(&encoded), (l, r));
+                    assert_eq!(depth(m.0, m.1), dirs.len() as i64);
+                    for d in 0..=dirs.len() + 1 {
+                        let expected = if d <= dirs.len() {
+                            let mut x = ((1, 1), (0, 1), (1, 0));
+                            for &c in &dirs[..d] {
+                                let ((p, q), l, r) = x;
+                                x = if c == 'L' {
+                                    ((p + l.0, q + l.1), l, (p, q))
+                                } else {
+                                    ((p + r.0, q + r.1), (p, q), r)
+                                };
+                            }
+                            Some(x.0)
+                        } else {
+                            None
+                        };
+                        assert_eq!(ancestor(m.0, m.1, d as i64), expected);
+                    }
+                    let mut left = dirs.clone();
+                    left.push('L');
+                    let mut right = dirs;
+                    right.push('R');
+                    next.push((left, (m.0 + l.0, m.1 + l.1), l, m));
+                    next.push((right, (m.0 + r.0, m.1 + r.1), m, r));
+                }
+                nodes = next;
+            }
+
+            assert_eq!(lca((1, 1), (5, 7)), (1, 1));
+            assert_eq!(lca((5, 7), (5, 7)), (5, 7));
+            assert_eq!(lca((2, 5), (1, 3)), (1, 3));
+            assert_eq!(lca((2, 5), (1, 4)), (1, 3));
+            for p1 in 1..25i64 {
+                for q1 in 1..25i64 {
+                    for p2 in 1..25i64 {
+                        for q2 in 1..25i64 {
+                            let (p, q) = lca((p1, q1), (p2, q2));
+                            let (l, r) = subtree_bounds(&path(p, q));
+                            let inside = |(a, b): (i64, i64)| {
+                                (a, b) == (p / gcd(p, q), q / gcd(p, q))
+                                    || l.0 * b < a * l.1 && a * r.1 < r.0 * b
+                            };
+                            let g1 = gcd(p1, q1);
+                            let g2 = gcd(p2, q2);
+                            assert!(inside((p1 / g1, q1 / g1)) || (p, q) == (p1 / g1, q1 / g1));
+                            assert!(inside((p2 / g2, q2 / g2)) || (p, q) == (p2 / g2, q2 / g2));
+                        }
+                    }
+                }
+            }
+            let big = (1u128 << 100) + 1;
+            assert_eq!(path(big, 1 << 100), [1, (1 << 100) - 1]);
+            assert_eq!(depth(big, 1 << 100), 1 << 100);
+        }
+
+        #[test]
+        fn test_best_rational_in_range() {
+            for lp in 0..20i64 {
+                for lq in 1..20i64 {
+                    for hp in 0..20i64 {
+                        for hq in 1..20i64 {
+                            if lp * hq > hp * lq {
+                                continue;
+                            }
+                            let expected = (1..=20)
+                                .flat_map(|q| (0..=20).map(move |p| (p, q)))
+                                .find(|&(p, q)| lp * q <= p * lq && p * hq <= hp * q)
+                                .unwrap();
+                            assert_eq!(best_rational_in_range((lp, lq), (hp, hq)), expected);
+                        }
+                    }
+                }
+            }
+            // pi
+            let lo = (3_141_592_653_589_793i128, 1_000_000_000_000_000);
+            let hi = (3_141_592_653_589_794i128, 1_000_000_000_000_000);
+            let (p, q) = best_rational_in_range(lo, hi);
+            assert!(lo.0 * q <= p * lo.1 && p * hi.1 <= hi.0 * q);
+            assert_eq!((p, q), (165_707_065, 52_746_197));
+            assert_eq!(best_rational_in_range((7, 3), (7, 3)), (7, 3));
+            assert_eq!(best_rational_in_range((0, 3), (7, 3)), (0, 1));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unreadable_literal)]
+    #![allow(clippy::cognitive_complexity)]
+    use super::*;
+    #[test]
+    fn test_pow_mod() {
+        assert_eq!(pow_mod(0, 0, 1), 0);
+        assert_eq!(pow_mod(0, 0, 3), 1);
+        assert_eq!(pow_mod(0, 0, 723), 1);
+        assert_eq!(pow_mod(0, 0, 998244353), 1);
+        assert_eq!(pow_mod(0, 0, 2u32.pow(31)), 1);
+
+        assert_eq!(pow_mod(0, 1, 1), 0);
+        assert_eq!(pow_mod(0, 1, 3), 0);
+        assert_eq!(pow_mod(0, 1, 723), 0);
+        assert_eq!(pow_mod(0, 1, 998244353), 0);
+        assert_eq!(pow_mod(0, 1, 2u32.pow(31)), 0);
+
+        assert_eq!(pow_mod(0, i64::MAX, 1), 0);
+        assert_eq!(pow_mod(0, i64::MAX, 3), 0);
+        assert_eq!(pow_mod(0, i64::MAX, 723), 0);
         assert_eq!(pow_mod(0, i64::MAX, 998244353), 0);
         assert_eq!(pow_mod(0, i64::MAX, 2u32.pow(31)), 0);
 
@@ -294,6 +1912,277 @@ mod tests {
         assert_eq!(crt(&a, &b), (39, 45));
     }
 
+    #[test]
+    fn test_pow_mod_u64() {
+        fn naive(x: u64, n: u64, m: u64) -> u64 {
+            let y = x % m;
+            (0..n).fold(1 % m, |r, _| (r as u128 * y as u128 % m as u128) as u64)
+        }
+        for &x in &[0, 1, 2, 3, 1 << 40, u64::MAX - 1, u64::MAX] {
+            for n in 0..20 {
+                for &m in &[1, 2, 7, 998_244_353, (1 << 61) - 1, u64::MAX - 58, u64::MAX] {
+                    assert_eq!(pow_mod_u64(x, n, m), naive(x, n, m));
+                }
+            }
+        }
+        // Fermat's little theorem for the largest 64-bit prime
+        let p = u64::MAX - 58;
+        for x in 1..100 {
+            assert_eq!(pow_mod_u64(x, p - 1, p), 1);
+        }
+        for m in 1..100 {
+            for x in 0..100 {
+                for n in 0..10 {
+                    assert_eq!(
+                        pow_mod_u64(x, n, m) as u32,
+                        pow_mod(x as i64, n as i64, m as u32)
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_inv_mod_u64() {
+        for m in 1..200u64 {
+            for x in 0..200u64 {
+                if internal_math::inv_gcd(x as i64, m as i64).0 != 1 {
+                    continue;
+                }
+                let y = inv_mod_u64(x, m);
+                assert!(y < m);
+                assert_eq!(x * y % m, 1 % m);
+            }
+        }
+        for &m in &[(1 << 61) - 1, u64::MAX - 58, u64::MAX] {
+            for &x in &[1, 2, 1 << 40, m - 1, m - 2, u64::MAX] {
+                if internal_math::inv_gcd_i128(x as i128, m as i128).0 != 1 {
+                    continue;
+                }
+                let y = inv_mod_u64(x, m);
+                assert!(y < m);
+                assert_eq!((x as u128 * y as u128 % m as u128) as u64, 1);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_inv_mod_u64_not_coprime() {
+        inv_mod_u64(6, u64::MAX - 1);
+    }
+
+    #[test]
+    fn test_ext_gcd() {
+        for a in -30i64..30 {
+            for b in -30i64..30 {
+                let (g, x, y) = ext_gcd(a, b);
+                if a == 0 && b == 0 {
+                    assert_eq!((g, x, y), (0, 1, 0));
+                    continue;
+                }
+                let mut h = 0;
+                for d in 1..=60 {
+                    if a % d == 0 && b % d == 0 {
+                        h = d;
+                    }
+                }
+                assert_eq!(g, h);
+                assert_eq!(a * x + b * y, g);
+            }
+        }
+        let (g, x, y) = ext_gcd(i64::MAX, i64::MAX - 1);
+        assert_eq!((g, x, y), (1, 1, -1));
+        let a = 1_000_000_000_000_000_000_000_000i128;
+        let b = 998_244_353i128.pow(3);
+        let (g, x, y) = ext_gcd(a, b);
+        assert_eq!(g, 1);
+        assert!(x.abs() <= b && y.abs() <= a);
+        // a * x + b * y = 1 holds in Z, so it holds in Z / 2^128 Z
+        assert_eq!(a.wrapping_mul(x).wrapping_add(b.wrapping_mul(y)), 1);
+    }
+
+    #[test]
+    fn test_iroot() {
+        for n in 0u64..2000 {
+            for k in 1..12 {
+                let x = iroot(n, k);
+                assert!(x.pow(k) <= n && n < (x + 1).pow(k));
+            }
+            assert_eq!(isqrt(n), iroot(n, 2));
+            assert_eq!(iroot(n as i32, 3) as u64, iroot(n, 3));
+        }
+        for &n in &[u64::MAX, u64::MAX - 1, (1 << 40) * 3 - 1] {
+            for k in 1..70 {
+                let x = iroot(n, k);
+                assert!(x.pow(k) <= n);
+                assert!(x
+                    .checked_add(1)
+                    .and_then(|x| x.checked_pow(k))
+                    .map_or(true, |y| y > n));
+            }
+        }
+        for x in (0..2000u64).chain(u32::MAX as u64 - 1000..=u32::MAX as u64) {
+            assert_eq!(isqrt(x * x), x);
+            assert_eq!(isqrt(x * x + 2 * x), x);
+            let v = x as u128 * x as u128 * 17;
+            let y = isqrt(v);
+            assert!(y * y <= v && v < (y + 1) * (y + 1));
+            assert_eq!(isqrt(x * x + x), x);
+        }
+        assert_eq!(isqrt(u64::MAX), u32::MAX as u64);
+        assert_eq!(isqrt(u128::MAX), u64::MAX as u128);
+        assert_eq!(iroot(u128::MAX, 3), 6_981_463_658_331);
+        assert_eq!(iroot(u128::MAX, 128), 1);
+        assert_eq!(iroot(i64::MAX, 63), 1);
+        assert_eq!(iroot(0u64, 1), 0);
+    }
+
+    #[test]
+    fn test_solve_linear_diophantine() {
+        for a in -12..12 {
+            for b in -12..12 {
+                if a == 0 && b == 0 {
+                    continue;
+                }
+                for c in -30..30 {
+                    let s = solve_linear_diophantine(a, b, c);
+                    let naive = (-40..=40)
+                        .flat_map(|x| (-40..=40).map(move |y| (x, y)))
+                        .filter(|&(x, y)| a * x + b * y == c)
+                        .count() as u128;
+                    let s = match s {
+                        Some(s) => s,
+                        None => {
+                            assert_eq!(naive, 0);
+                            continue;
+                        }
+                    };
+                    assert!(s.dx >= 0);
+                    if s.dx > 0 {
+                        assert!(0 <= s.x && s.x < s.dx);
+                    } else {
+                        assert!(0 <= s.y && s.y < s.dy);
+                    }
+                    for t in -3..3 {
+                        let (x, y) = s.nth(t);
+                        assert_eq!(a * x + b * y, c);
+                    }
+                    assert_eq!(s.count(-40..=40, -40..=40), naive);
+                    assert_eq!(s.count(RangeInclusive::new(5, 4), -40..=40), 0);
+                }
+            }
+        }
+        let s = solve_linear_diophantine(1_000_000_007, 998_244_353, 1).unwrap();
+        let (x, y) = (s.x as i128, s.y as i128);
+        assert_eq!(x * 1_000_000_007 + y * 998_244_353, 1);
+        let s = solve_linear_diophantine(i64::MAX, i64::MAX - 1, i64::MAX).unwrap();
+        assert_eq!(
+            s,
+            LinearDiophantine {
+                x: 1,
+                y: 0,
+                dx: i64::MAX - 1,
+                dy: -i64::MAX
+            }
+        );
+        assert_eq!(s.count(0..=i64::MAX, 0..=i64::MAX), 1);
+        assert_eq!(s.count(i64::MIN..=i64::MAX, i64::MIN..=i64::MAX), 3);
+        let s = solve_linear_diophantine(2, i64::MIN, 0).unwrap();
+        assert_eq!((s.dx, s.dy), (1 << 62, 1));
+        assert_eq!(s.nth(-1), (-(1 << 62), -1));
+        let s = solve_linear_diophantine(1, 0, 5).unwrap();
+        assert_eq!(s.count(i64::MIN..=i64::MAX, i64::MIN..=i64::MAX), 1 << 64);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_linear_diophantine_nth_overflow() {
+        // y = -t
+        solve_linear_diophantine(1, 1, 0).unwrap().nth(i64::MIN);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_solve_linear_diophantine_step_overflow() {
+        // dx = 2^63
+        solve_linear_diophantine(1, i64::MIN, 0);
+    }
+
+    #[test]
+    fn test_crt_i128() {
+        let a = [44, 23, 13];
+        let b = [13, 50, 22];
+        assert_eq!(crt_i128(&a, &b), (1773, 7150));
+        let a = [0, 3, 4];
+        let b = [1, 9, 5];
+        assert_eq!(crt_i128(&a, &b), (39, 45));
+        assert_eq!(crt_i128(&[1, 2], &[4, 6]), (0, 0));
+        assert_eq!(crt_i128(&[], &[]), (0, 1));
+
+        let m = [
+            999_999_999_999_999_989,
+            1_000_000_000_000_000_003,
+            1_000_000_000_000_000_009,
+        ];
+        let (y, z) = crt_i128(&[-1, 1 << 40], &m[1..]);
+        assert_eq!(z, m[1] * m[2]);
+        assert_eq!(y.rem_euclid(m[1]), m[1] - 1);
+        assert_eq!(y % m[2], 1 << 40);
+        let m = [1 << 62, 3i128.pow(39)];
+        let r = [m[0] - 1, 0];
+        let (y, z) = crt_i128(&r, &m);
+        assert_eq!(z, m[0] * m[1]);
+        assert_eq!((y % m[0], y % m[1]), (m[0] - 1, 0));
+        // shared factors
+        let (y, z) = crt_i128(&[7, 7 + (1 << 80)], &[1 << 72, 1 << 100]);
+        assert_eq!((y, z), (7 + (1 << 80), 1 << 100));
+        assert_eq!(crt_i128(&[7, 7 + (1 << 70)], &[1 << 72, 1 << 100]), (0, 0));
+    }
+
+    #[test]
+    fn test_garner() {
+        assert_eq!(garner(&[], &[], 7), 0);
+        assert_eq!(garner(&[], &[], 1), 0);
+        for m0 in 1..12 {
+            for m1 in 1..12 {
+                if internal_math::inv_gcd(m0, m1).0 != 1 {
+                    continue;
+                }
+                for r0 in -12..12 {
+                    for r1 in -12..12 {
+                        let (y, z) = crt(&[r0, r1], &[m0, m1]);
+                        assert_eq!(z, m0 * m1);
+                        for t in 1..20 {
+                            assert_eq!(garner(&[r0, r1], &[m0, m1], t), y % t);
+                        }
+                    }
+                }
+            }
+        }
+
+        let m = [
+            999_999_999_999_999_989,
+            1_000_000_000_000_000_003,
+            1_000_000_000_000_000_009,
+            998_244_353,
+        ];
+        let r = [123_456_789_012_345_678, -5, i64::MAX, 1];
+        let (y, z) = crt_i128(
+            &r[1..3].iter().map(|&x| x as i128).collect::<Vec<_>>(),
+            &m[1..3].iter().map(|&x| x as i128).collect::<Vec<_>>(),
+        );
+        assert_eq!(z, m[1] as i128 * m[2] as i128);
+        for &t in &[1, 2, 1_000_000_007, i64::MAX] {
+            assert_eq!(garner(&r[1..3], &m[1..3], t) as i128, y % t as i128);
+        }
+        // the product of all the moduli exceeds i128
+        for (&ri, &mi) in r.iter().zip(&m) {
+            let x = garner(&r, &m, mi);
+            assert_eq!(x, internal_math::safe_mod(ri, mi));
+        }
+    }
+
     #[test]
     fn test_floor_sum() {
         assert_eq!(floor_sum(0, 1, 0, 0), 0);
@@ -314,6 +2203,270 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_is_prime_u64() {
+        assert!(!is_prime_u64(0));
+        assert!(!is_prime_u64(1));
+        assert!(is_prime_u64(2));
+        assert!(is_prime_u64(998_244_353));
+        assert!(is_prime_u64(4_611_686_018_427_387_847)); // 2^62 - 57
+        assert!(!is_prime_u64(4_611_686_018_427_387_847 * 3));
+        assert!(!is_prime_u64(u64::MAX));
+    }
+
+    #[test]
+    fn test_pollard_rho() {
+        // Small composites often make a whole round collapse to `n`, which forces retries with other `c`.
+        for n in (9..20000).step_by(2).filter(|&n| !is_prime_u64(n)) {
+            let d = pollard_rho(n);
+            assert!(1 < d && d < n && n % d == 0, "n = {}, d = {}", n, d);
+        }
+    }
+
+    #[test]
+    fn test_factorize() {
+        for n in 1..3000 {
+            let factors = factorize(n);
+            assert_eq!(factors.iter().map(|&(p, e)| p.pow(e)).product::<u64>(), n);
+            assert!(factors.windows(2).all(|w| w[0].0 < w[1].0));
+            assert!(factors.iter().all(|&(p, _)| is_prime_u64(p)));
+        }
+        assert_eq!(factorize(1), []);
+        assert_eq!(
+            factorize(4_611_686_018_427_387_847),
+            [(4_611_686_018_427_387_847, 1)]
+        );
+        assert_eq!(
+            factorize(999_999_999_999_999_988),
+            [(2, 2), (11, 1), (22_727_272_727_272_727, 1)],
+        );
+        // products of two large primes, including squares
+        assert_eq!(
+            factorize(4_294_967_291 * 4_294_967_279),
+            [(4_294_967_279, 1), (4_294_967_291, 1)]
+        );
+        assert_eq!(
+            factorize(4_294_967_291 * 4_294_967_291),
+            [(4_294_967_291, 2)]
+        );
+        assert_eq!(factorize(1 << 63), [(2, 63)]);
+        assert_eq!(
+            factorize(u64::MAX),
+            [
+                (3, 1),
+                (5, 1),
+                (17, 1),
+                (257, 1),
+                (641, 1),
+                (65537, 1),
+                (6_700_417, 1)
+            ],
+        );
+        assert_eq!(
+            factorize(1_000_003 * 1_000_033 * 1_000_037),
+            [(1_000_003, 1), (1_000_033, 1), (1_000_037, 1)]
+        );
+    }
+
+    #[test]
+    fn test_divisors() {
+        assert_eq!(divisors(1), [1]);
+        assert_eq!(divisors(12), [1, 2, 3, 4, 6, 12]);
+        for n in 1..1000 {
+            assert_eq!(
+                divisors(n),
+                (1..=n).filter(|d| n % d == 0).collect::<Vec<_>>()
+            );
+        }
+        assert_eq!(divisors(735_134_400).len(), 1344);
+    }
+
+    #[test]
+    fn test_sieve() {
+        for n in 0..50 {
+            let sieve = Sieve::new(n);
+            assert_eq!(sieve.max(), n);
+            assert_eq!(
+                sieve.primes(),
+                (0..=n)
+                    .filter(|&x| internal_math::is_prime(x as i32))
+                    .collect::<Vec<_>>()
+            );
+        }
+
+        let n = 100_000;
+        let sieve = Sieve::new(n);
+        assert_eq!(sieve.primes().len(), 9592);
+        let phi = sieve.totient_table();
+        let mu = sieve.mobius_table();
+        let d = sieve.divisor_count_table();
+        assert_eq!((phi[0], mu[0], d[0]), (0, 0, 0));
+        for x in 1..=n {
+            assert_eq!(sieve.is_prime(x), internal_math::is_prime(x as i32));
+            let factors = sieve.factorize(x);
+            assert_eq!(
+                factors,
+                factorize(x as u64)
+                    .into_iter()
+                    .map(|(p, e)| (p as usize, e))
+                    .collect::<Vec<_>>()
+            );
+            if x >= 2 {
+                assert_eq!(sieve.smallest_prime_factor(x), factors[0].0);
+            }
+            assert_eq!(
+                phi[x],
+                factors
+                    .iter()
+                    .map(|&(p, e)| p.pow(e - 1) * (p - 1))
+                    .product::<usize>()
+            );
+            assert_eq!(
+                mu[x],
+                if factors.iter().any(|&(_, e)| e > 1) {
+                    0
+                } else if factors.len() % 2 == 0 {
+                    1
+                } else {
+                    -1
+                }
+            );
+            assert_eq!(d[x], factors.iter().map(|&(_, e)| e + 1).product::<u32>());
+        }
+    }
+
+    #[test]
+    fn test_segmented_sieve() {
+        assert_eq!(segmented_sieve(0, 0), []);
+        assert_eq!(segmented_sieve(0, 2), []);
+        assert_eq!(segmented_sieve(0, 3), [2]);
+        assert_eq!(segmented_sieve(5, 5), []);
+        assert_eq!(segmented_sieve(0, 30), [2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+        let sieve = Sieve::new(10_000);
+        for l in (0..10_000).step_by(97) {
+            for r in (l..=10_000).step_by(1013) {
+                assert_eq!(
+                    segmented_sieve(l as u64, r as u64),
+                    sieve
+                        .primes()
+                        .iter()
+                        .filter(|&&p| l <= p && p < r)
+                        .map(|&p| p as u64)
+                        .collect::<Vec<_>>()
+                );
+            }
+        }
+        let (l, r) = (1_000_000_000_000 - 1000, 1_000_000_000_000 + 1000);
+        assert_eq!(
+            segmented_sieve(l, r),
+            (l..r).filter(|&x| is_prime_u64(x)).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_prime_count() {
+        let sieve = Sieve::new(10_000);
+        let mut count = 0;
+        for n in 0..=10_000 {
+            if sieve.is_prime(n) {
+                count += 1;
+            }
+            assert_eq!(prime_count(n as u64), count);
+        }
+        assert_eq!(prime_count(1_000_000), 78_498);
+        assert_eq!(prime_count(999_999_999), 50_847_534);
+    }
+
+    #[test]
+    fn test_multiplicative_prefix_sum() {
+        type Mint = crate::ModInt998244353;
+
+        let n = 20_000;
+        let sieve = Sieve::new(n);
+        let phi = sieve.totient_table();
+        let mu = sieve.mobius_table();
+        let d = sieve.divisor_count_table();
+        for m in (0..=n as u64).step_by(97).chain(n as u64 - 10..=n as u64) {
+            let count = prime_sum_table(m, |_| 1i64, |v| v as i64 - 1);
+            let sum = prime_sum_table(m, |p| p as i64, |v| (v * (v + 1) / 2) as i64 - 1);
+            let phi_p = sum
+                .iter()
+                .zip(&count)
+                .map(|(s, c)| s - c)
+                .collect::<Vec<_>>();
+            let mu_p = count.iter().map(|c| -c).collect::<Vec<_>>();
+            let d_p = count.iter().map(|c| 2 * c).collect::<Vec<_>>();
+            let m_ = m as usize;
+            assert_eq!(
+                multiplicative_prefix_sum(m, &phi_p, |p, e| ((p - 1) * p.pow(e - 1)) as i64),
+                phi[..=m_].iter().sum::<usize>() as i64
+            );
+            assert_eq!(
+                multiplicative_prefix_sum(m, &mu_p, |_, e| if e == 1 { -1 } else { 0 }),
+                mu[..=m_].iter().sum::<i32>() as i64
+            );
+            assert_eq!(
+                multiplicative_prefix_sum(m, &d_p, |_, e| e as i64 + 1),
+                d[..=m_].iter().sum::<u32>() as i64
+            );
+        }
+
+        // \sum_{i <= n} sigma_1(i) mod 998244353 over ModInt
+        let n = 100_000_000u64;
+        let count = prime_sum_table(n, |_| Mint::new(1), |v| Mint::new(v) - Mint::new(1));
+        let sum = prime_sum_table(n, Mint::new, |v| {
+            Mint::new(v) * Mint::new(v + 1) / Mint::new(2) - Mint::new(1)
+        });
+        let sigma_p = sum
+            .iter()
+            .zip(&count)
+            .map(|(&s, &c)| s + c)
+            .collect::<Vec<_>>();
+        let sigma =
+            |p: u64, e: u32| (Mint::new(p).pow(e as u64 + 1) - Mint::new(1)) / Mint::new(p - 1);
+        // \sum_{i <= n} sigma_1(i) = \sum_{d <= n} d floor(n / d)
+        let mut expected = Mint::new(0);
+        let mut l = 1;
+        while l <= n {
+            let q = n / l;
+            let r = n / q;
+            let s = |x: u64| Mint::new(x) * Mint::new(x + 1) / Mint::new(2);
+            expected += (s(r) - s(l - 1)) * Mint::new(q);
+            l = r + 1;
+        }
+        assert_eq!(multiplicative_prefix_sum(n, &sigma_p, sigma), expected);
+    }
+
+    #[test]
+    fn test_quotient_ranges() {
+        for n in 0..300u64 {
+            let mut i = 1;
+            for (q, r) in quotient_ranges(n) {
+                assert_eq!(r.start, i);
+                assert!(r.start < r.end);
+                assert!(r.clone().all(|i| n / i == q));
+                assert_ne!(n / r.end, q);
+                i = r.end;
+            }
+            assert_eq!(i, n + 1);
+
+            let mut i = 1;
+            for (q, r) in ceil_quotient_ranges(n) {
+                assert_eq!(r.start, i);
+                assert!(r.start < r.end);
+                assert!(r.clone().all(|i| (n + i - 1) / i == q));
+                assert!(r.end == n + 1 || (n + r.end - 1) / r.end != q);
+                i = r.end;
+            }
+            assert_eq!(i, n + 1);
+        }
+        let n = 1_000_000_000_000u64;
+        assert_eq!(quotient_ranges(n).count(), 1_999_999);
+        let n = u64::MAX - 1;
+        assert_eq!(quotient_ranges(n).next(), Some((n, 1..2)));
+        assert_eq!(ceil_quotient_ranges(n).nth(1), Some((n / 2, 2..3)));
+    }
+
     #[allow(clippy::many_single_char_names)]
     fn floor_sum_naive(n: i64, m: i64, a: i64, b: i64) -> i64 {
         let mut ans = 0;
@@ -49,6 +49,7 @@
 //! [`ModInt`]: ./type.ModInt.html
 
 use crate::internal_math;
+use crate::internal_type_traits::{One, Zero};
 use std::{
     cell::RefCell,
     convert::{Infallible, TryInto as _},
@@ -898,6 +899,20 @@ macro_rules! impl_basic_traits {
             }
         }
 
+        impl<$generic_param: $generic_param_bound> Zero for $self {
+            #[inline]
+            fn zero() -> Self {
+                Self::raw(0)
+            }
+        }
+
+        impl<$generic_param: $generic_param_bound> One for $self {
+            #[inline]
+            fn one() -> Self {
+                Self::new(1)
+            }
+        }
+
         impl_basic_traits!($($rest)*);
     };
 }
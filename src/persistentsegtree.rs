@@ -0,0 +1,400 @@
+//! A fully persistent segment tree.
+
+use crate::segtree::Monoid;
+use std::iter::FromIterator;
+use std::ops::{Bound, RangeBounds};
+
+/// A handle to a version of a [`PersistentSegtree`].
+///
+/// [`PersistentSegtree`]: ./struct.PersistentSegtree.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Version(usize);
+
+/// A segment tree which keeps all of its versions.
+///
+/// [`set`] does not modify a version but creates a new one, sharing all but $O(\log n)$ nodes with the old version.
+/// All the queries take the version to be queried.
+///
+/// # Example
+///
+/// ```
+/// use ac_library::{Additive, PersistentSegtree};
+///
+/// let mut segtree = PersistentSegtree::<Additive<i32>>::from(vec![1, 2, 3]);
+/// let v0 = segtree.initial();
+/// let v1 = segtree.set(v0, 0, 10);
+/// let v2 = segtree.set(v1, 2, 30);
+/// assert_eq!(segtree.prod(v0, ..), 6);
+/// assert_eq!(segtree.prod(v1, ..), 15);
+/// assert_eq!(segtree.prod(v2, ..), 42);
+/// // branch from an old version
+/// let v3 = segtree.set(v0, 1, 20);
+/// assert_eq!(segtree.prod(v3, ..), 24);
+/// ```
+///
+/// [`set`]: #method.set
+#[derive(Clone)]
+pub struct PersistentSegtree<M>
+where
+    M: Monoid,
+{
+    n: usize,
+    d: Vec<M::S>,
+    children: Vec<[usize; 2]>,
+}
+
+impl<M: Monoid> Default for PersistentSegtree<M> {
+    fn default() -> Self {
+        PersistentSegtree::new(0)
+    }
+}
+impl<M: Monoid> PersistentSegtree<M> {
+    pub fn new(n: usize) -> PersistentSegtree<M> {
+        vec![M::identity(); n].into()
+    }
+}
+impl<M: Monoid> From<Vec<M::S>> for PersistentSegtree<M> {
+    fn from(v: Vec<M::S>) -> Self {
+        let n = v.len();
+        let mut ret = PersistentSegtree {
+            n,
+            d: Vec::with_capacity(2 * n),
+            children: Vec::with_capacity(2 * n),
+        };
+        if n == 0 {
+            ret.push(M::identity(), [0; 2]);
+        } else {
+            ret.build(&v);
+        }
+        ret
+    }
+}
+impl<M: Monoid> FromIterator<M::S> for PersistentSegtree<M> {
+    fn from_iter<T: IntoIterator<Item = M::S>>(iter: T) -> Self {
+        let v = iter.into_iter().collect::<Vec<_>>();
+        v.into()
+    }
+}
+impl<M: Monoid> PersistentSegtree<M> {
+    /// Returns the version created by the constructor.
+    pub fn initial(&self) -> Version {
+        // the root of the initial tree is the last node built
+        Version(if self.n == 0 { 0 } else { 2 * self.n - 2 })
+    }
+
+    /// Returns a new version in which the $p$-th element of `version` is replaced with $x$.
+    ///
+    /// # Constraints
+    ///
+    /// - $0 \leq p < n$
+    ///
+    /// # Complexity
+    ///
+    /// - $O(\log n)$ time and space
+    pub fn set(&mut self, version: Version, p: usize, x: M::S) -> Version {
+        assert!(p < self.n);
+        Version(self.set_rec(version.0, 0, self.n, p, x))
+    }
+
+    /// Returns the $p$-th element of `version`.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(\log n)$
+    pub fn get(&self, version: Version, p: usize) -> M::S {
+        assert!(p < self.n);
+        let (mut node, mut l, mut r) = (version.0, 0, self.n);
+        while r - l > 1 {
+            let m = (l + r) / 2;
+            if p < m {
+                node = self.children[node][0];
+                r = m;
+            } else {
+                node = self.children[node][1];
+                l = m;
+            }
+        }
+        self.d[node].clone()
+    }
+
+    /// Returns the product of the elements of `version` in `range`.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(\log n)$
+    pub fn prod<R>(&self, version: Version, range: R) -> M::S
+    where
+        R: RangeBounds<usize>,
+    {
+        let r = match range.end_bound() {
+            Bound::Included(r) => r + 1,
+            Bound::Excluded(r) => *r,
+            Bound::Unbounded => self.n,
+        };
+        let l = match range.start_bound() {
+            Bound::Included(l) => *l,
+            Bound::Excluded(l) => l + 1,
+            Bound::Unbounded => 0,
+        };
+        assert!(l <= r && r <= self.n);
+        if l == r {
+            return M::identity();
+        }
+        self.prod_rec(version.0, 0, self.n, l, r)
+    }
+
+    /// Returns the product of all the elements of `version`.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(1)$
+    pub fn all_prod(&self, version: Version) -> M::S {
+        self.d[version.0].clone()
+    }
+
+    /// Performs [`Segtree::max_right`] on `version`.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(\log n)$
+    ///
+    /// [`Segtree::max_right`]: ../segtree/struct.Segtree.html#method.max_right
+    pub fn max_right<F>(&self, version: Version, l: usize, f: F) -> usize
+    where
+        F: Fn(&M::S) -> bool,
+    {
+        assert!(l <= self.n);
+        assert!(f(&M::identity()));
+        if l == self.n {
+            return self.n;
+        }
+        let mut sm = M::identity();
+        self.max_right_rec(version.0, 0, self.n, l, &f, &mut sm)
+            .unwrap_or(self.n)
+    }
+
+    /// Performs [`Segtree::min_left`] on `version`.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(\log n)$
+    ///
+    /// [`Segtree::min_left`]: ../segtree/struct.Segtree.html#method.min_left
+    pub fn min_left<F>(&self, version: Version, r: usize, f: F) -> usize
+    where
+        F: Fn(&M::S) -> bool,
+    {
+        assert!(r <= self.n);
+        assert!(f(&M::identity()));
+        if r == 0 {
+            return 0;
+        }
+        let mut sm = M::identity();
+        self.min_left_rec(version.0, 0, self.n, r, &f, &mut sm)
+            .unwrap_or(0)
+    }
+
+    fn push(&mut self, x: M::S, children: [usize; 2]) -> usize {
+        self.d.push(x);
+        self.children.push(children);
+        self.d.len() - 1
+    }
+
+    /// Builds the tree in post-order, so that the root is the last node.
+    fn build(&mut self, v: &[M::S]) -> usize {
+        if v.len() == 1 {
+            return self.push(v[0].clone(), [0; 2]);
+        }
+        let m = v.len() / 2;
+        let a = self.build(&v[..m]);
+        let b = self.build(&v[m..]);
+        let x = M::binary_operation(&self.d[a], &self.d[b]);
+        self.push(x, [a, b])
+    }
+
+    fn set_rec(&mut self, node: usize, l: usize, r: usize, p: usize, x: M::S) -> usize {
+        if r - l == 1 {
+            return self.push(x, [0; 2]);
+        }
+        let m = (l + r) / 2;
+        let [mut a, mut b] = self.children[node];
+        if p < m {
+            a = self.set_rec(a, l, m, p, x);
+        } else {
+            b = self.set_rec(b, m, r, p, x);
+        }
+        let x = M::binary_operation(&self.d[a], &self.d[b]);
+        self.push(x, [a, b])
+    }
+
+    fn prod_rec(&self, node: usize, nl: usize, nr: usize, l: usize, r: usize) -> M::S {
+        if l <= nl && nr <= r {
+            return self.d[node].clone();
+        }
+        let m = (nl + nr) / 2;
+        let [a, b] = self.children[node];
+        if r <= m {
+            self.prod_rec(a, nl, m, l, r)
+        } else if m <= l {
+            self.prod_rec(b, m, nr, l, r)
+        } else {
+            M::binary_operation(
+                &self.prod_rec(a, nl, m, l, r),
+                &self.prod_rec(b, m, nr, l, r),
+            )
+        }
+    }
+
+    /// Returns the answer if it is in $[\max(l, nl), nr)$; otherwise multiplies the product of the range to `sm`.
+    fn max_right_rec<F>(
+        &self,
+        node: usize,
+        nl: usize,
+        nr: usize,
+        l: usize,
+        f: &F,
+        sm: &mut M::S,
+    ) -> Option<usize>
+    where
+        F: Fn(&M::S) -> bool,
+    {
+        if nr <= l {
+            return None;
+        }
+        if l <= nl {
+            let res = M::binary_operation(sm, &self.d[node]);
+            if f(&res) {
+                *sm = res;
+                return None;
+            }
+            if nr - nl == 1 {
+                return Some(nl);
+            }
+        }
+        let m = (nl + nr) / 2;
+        let [a, b] = self.children[node];
+        self.max_right_rec(a, nl, m, l, f, sm)
+            .or_else(|| self.max_right_rec(b, m, nr, l, f, sm))
+    }
+
+    /// Returns the answer if it is in $(nl, \min(r, nr)]$; otherwise multiplies the product of the range to `sm` from the left.
+    fn min_left_rec<F>(
+        &self,
+        node: usize,
+        nl: usize,
+        nr: usize,
+        r: usize,
+        f: &F,
+        sm: &mut M::S,
+    ) -> Option<usize>
+    where
+        F: Fn(&M::S) -> bool,
+    {
+        if r <= nl {
+            return None;
+        }
+        if nr <= r {
+            let res = M::binary_operation(&self.d[node], sm);
+            if f(&res) {
+                *sm = res;
+                return None;
+            }
+            if nr - nl == 1 {
+                return Some(nr);
+            }
+        }
+        let m = (nl + nr) / 2;
+        let [a, b] = self.children[node];
+        self.min_left_rec(b, m, nr, r, f, sm)
+            .or_else(|| self.min_left_rec(a, nl, m, r, f, sm))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::segtree::{Additive, Max};
+    use rand::Rng;
+
+    #[test]
+    fn test_persistent_segtree_random() {
+        let mut rng = rand::thread_rng();
+        for n in 0..20 {
+            let init = (0..n).map(|_| rng.gen_range(0, 100)).collect::<Vec<i64>>();
+            let mut segtree: PersistentSegtree<Max<i64>> = init.iter().copied().collect();
+            let mut versions = vec![(segtree.initial(), init)];
+            for _ in 0..300 {
+                let (version, naive) = versions[rng.gen_range(0, versions.len())].clone();
+                let prod =
+                    |l: usize, r: usize| naive[l..r].iter().copied().max().unwrap_or(i64::MIN);
+                match rng.gen_range(0, 4) {
+                    0 if n > 0 => {
+                        let p = rng.gen_range(0, n);
+                        let x = rng.gen_range(0, 100);
+                        let mut next = naive.clone();
+                        next[p] = x;
+                        versions.push((segtree.set(version, p, x), next));
+                    }
+                    1 => {
+                        let l = rng.gen_range(0, n + 1);
+                        let r = rng.gen_range(l, n + 1);
+                        assert_eq!(segtree.prod(version, l..r), prod(l, r));
+                        assert_eq!(segtree.prod(version, ..r), prod(0, r));
+                        assert_eq!(segtree.prod(version, l..), prod(l, n));
+                        if l < r {
+                            assert_eq!(segtree.get(version, l), naive[l]);
+                            assert_eq!(segtree.prod(version, l..=r - 1), prod(l, r));
+                        }
+                    }
+                    2 => {
+                        let l = rng.gen_range(0, n + 1);
+                        let t = rng.gen_range(0, 110);
+                        let expected = (l..=n).take_while(|&r| prod(l, r) < t).last().unwrap();
+                        assert_eq!(segtree.max_right(version, l, |&x| x < t), expected);
+                    }
+                    _ => {
+                        let r = rng.gen_range(0, n + 1);
+                        let t = rng.gen_range(0, 110);
+                        let expected = (0..=r)
+                            .rev()
+                            .take_while(|&l| prod(l, r) < t)
+                            .last()
+                            .unwrap();
+                        assert_eq!(segtree.min_left(version, r, |&x| x < t), expected);
+                    }
+                }
+                assert_eq!(segtree.all_prod(version), prod(0, n));
+            }
+        }
+    }
+
+    #[test]
+    fn test_persistent_segtree_kth_smallest() {
+        // the k-th smallest value in a[l..r] by the versions of the prefixes of a
+        let a = [5, 1, 4, 1, 5, 9, 2, 6, 5, 3];
+        let mut segtree = PersistentSegtree::<Additive<usize>>::new(10);
+        let mut versions = vec![segtree.initial()];
+        for &x in &a {
+            let last = *versions.last().unwrap();
+            let count = segtree.get(last, x);
+            versions.push(segtree.set(last, x, count + 1));
+        }
+        for l in 0..a.len() {
+            for r in l + 1..=a.len() {
+                let mut sorted = a[l..r].to_vec();
+                sorted.sort_unstable();
+                for (k, &expected) in sorted.iter().enumerate() {
+                    // the smallest x such that #{i in [l, r) | a_i <= x} > k
+                    let (vl, vr) = (versions[l], versions[r]);
+                    let mut x = 0;
+                    while segtree.prod(vr, ..=x) - segtree.prod(vl, ..=x) <= k {
+                        x += 1;
+                    }
+                    assert_eq!(x, expected);
+                }
+            }
+        }
+        // the space is O(n + q log n)
+        assert!(segtree.d.len() <= 2 * 10 + 10 * 6);
+    }
+}
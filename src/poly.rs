@@ -0,0 +1,888 @@
+//! Functions on polynomials over $\mathbb{Z}/p\mathbb{Z}$, built on [`convolution`].
+//!
+//! A polynomial $f(x) = \sum_{i = 0}^{n - 1} f_i x^i$ is represented as the slice $f_0, f_1, \ldots, f_{n - 1}$.
+//!
+//! It also provides combinatorial sequences (Bernoulli numbers, Stirling numbers, partition numbers, ...) computed via formal power series.
+//!
+//! [`convolution`]: ../convolution/fn.convolution.html
+
+use crate::{
+    convolution::convolution,
+    modint::{ModIntBase, Modulus, StaticModInt},
+};
+
+/// Evaluates a polynomial at a geometric progression by the chirp z-transform.
+///
+/// Returns $f(a), f(ar), f(ar^2), \ldots, f(ar^{m - 1})$.
+///
+/// # Constraints
+///
+/// - `M::VALUE` satisfies the constraints of [`convolution`] for the length $2|f| + m$.
+///
+/// # Complexity
+///
+/// - $O((n + m) \log (n + m))$ where $n = |f|$.
+///
+/// # Example
+///
+/// ```
+/// use ac_library::{poly::chirp_z, ModInt998244353 as Mint};
+///
+/// // f(x) = 1 + 2x + 3x^2
+/// let f = [Mint::new(1), Mint::new(2), Mint::new(3)];
+/// assert_eq!(
+///     chirp_z(&f, Mint::new(1), Mint::new(2), 3),
+///     [Mint::new(6), Mint::new(17), Mint::new(57)],
+/// );
+/// ```
+///
+/// [`convolution`]: ../convolution/fn.convolution.html
+pub fn chirp_z<M: Modulus>(
+    f: &[StaticModInt<M>],
+    a: StaticModInt<M>,
+    r: StaticModInt<M>,
+    m: usize,
+) -> Vec<StaticModInt<M>> {
+    let n = f.len();
+    if n == 0 || m == 0 {
+        return vec![StaticModInt::raw(0); m];
+    }
+    if r.val() == 0 {
+        // f(a), f(0), f(0), ...
+        let mut ret = vec![f[0]; m];
+        ret[0] = eval(f, a);
+        return ret;
+    }
+
+    // ij = t(i + j) - t(i) - t(j) where t(k) = k(k - 1)/2, which holds for any r != 0 without a square root of r.
+    let triangular_powers = |r: StaticModInt<M>, len: usize| {
+        let mut ret = Vec::with_capacity(len);
+        let (mut cur, mut step) = (StaticModInt::new(1), StaticModInt::new(1));
+        for _ in 0..len {
+            ret.push(cur);
+            cur *= step;
+            step *= r;
+        }
+        ret
+    };
+    let pw = triangular_powers(r, n + m - 1);
+    let ipw = triangular_powers(r.inv(), std::cmp::max(n, m));
+
+    let mut c = Vec::with_capacity(n);
+    let mut ak = StaticModInt::new(1);
+    for j in 0..n {
+        c.push(f[j] * ak * ipw[j]);
+        ak *= a;
+    }
+    c.reverse();
+    let g = convolution(&c, &pw);
+    (0..m).map(|i| g[n - 1 + i] * ipw[i]).collect()
+}
+
+/// Given the values of a polynomial at $0, 1, \ldots, n - 1$, evaluates it at $c, c + 1, \ldots, c + m - 1$.
+///
+/// `values` is $f(0), f(1), \ldots, f(n - 1)$ where $\deg f < n$.
+/// Returns $f(c), f(c + 1), \ldots, f(c + m - 1)$.
+///
+/// # Constraints
+///
+/// - $n \leq$ `M::VALUE`
+/// - `M::VALUE` is a prime number satisfying the constraints of [`convolution`] for the length $2n + m$.
+///
+/// # Complexity
+///
+/// - $O((n + m) \log (n + m))$
+///
+/// # Example
+///
+/// ```
+/// use ac_library::{poly::shift_of_sampling_points, ModInt998244353 as Mint};
+///
+/// // f(x) = x^2
+/// let values = [Mint::new(0), Mint::new(1), Mint::new(4)];
+/// assert_eq!(
+///     shift_of_sampling_points(&values, Mint::new(10), 3),
+///     [Mint::new(100), Mint::new(121), Mint::new(144)],
+/// );
+/// ```
+///
+/// [`convolution`]: ../convolution/fn.convolution.html
+pub fn shift_of_sampling_points<M: Modulus>(
+    values: &[StaticModInt<M>],
+    c: StaticModInt<M>,
+    m: usize,
+) -> Vec<StaticModInt<M>> {
+    let n = values.len();
+    if n == 0 || m == 0 {
+        return vec![StaticModInt::raw(0); m];
+    }
+    assert!(n as u64 <= M::VALUE as u64);
+
+    // f(x) = (\prod_j (x - j)) \sum_i a_i / (x - i) where a_i = f(i) / (i! (n - 1 - i)! (-1)^{n - 1 - i}).
+    // For x = c + k, x - i = s_{k + n - 1 - i} where s_t = c - (n - 1) + t.
+    // s_t = 0 only if x is one of the sampling points, whose value is already known.
+    let (_, ifact) = factorials::<StaticModInt<M>>(n);
+    let a = (0..n)
+        .map(|i| {
+            let x = values[i] * ifact[i] * ifact[n - 1 - i];
+            if (n - 1 - i) % 2 == 0 {
+                x
+            } else {
+                -x
+            }
+        })
+        .collect::<Vec<_>>();
+    let len = n + m - 1;
+    let s = (0..len)
+        .map(|t| c - StaticModInt::new(n - 1) + StaticModInt::new(t))
+        .collect::<Vec<_>>();
+    let b = inv_or_zero(&s);
+    let g = convolution(&a, &b);
+
+    // \prod_{t = k}^{k + n - 1} s_t as the product of a suffix of one block and a prefix of the next
+    let mut suf = s.clone();
+    let mut pre = s;
+    for t in (0..len).rev() {
+        if (t + 1) % n != 0 && t + 1 < len {
+            suf[t] = suf[t] * suf[t + 1];
+        }
+    }
+    for t in 0..len {
+        if t % n != 0 {
+            pre[t] = pre[t] * pre[t - 1];
+        }
+    }
+
+    (0..m)
+        .map(|k| {
+            let x = c + StaticModInt::new(k);
+            if (x.val() as usize) < n {
+                return values[x.val() as usize];
+            }
+            let r = k + n - 1;
+            let window = if k % n == 0 { suf[k] } else { suf[k] * pre[r] };
+            window * g[r]
+        })
+        .collect()
+}
+
+/// Given the values of a polynomial at $0, 1, \ldots, n - 1$, evaluates it at $x$ by Lagrange interpolation.
+///
+/// `values` is $f(0), f(1), \ldots, f(n - 1)$ where $\deg f < n$.
+///
+/// # Constraints
+///
+/// - $n \leq$ `Z::modulus()`
+/// - `Z::modulus()` is a prime number.
+///
+/// # Complexity
+///
+/// - $O(n + \log p)$
+///
+/// # Example
+///
+/// ```
+/// use ac_library::{poly::lagrange_interpolation_at, ModInt1000000007 as Mint};
+///
+/// // f(x) = x^2
+/// let values = [Mint::new(0), Mint::new(1), Mint::new(4)];
+/// assert_eq!(
+///     lagrange_interpolation_at(&values, Mint::new(1_000_000)),
+///     Mint::new(1_000_000_000_000u64),
+/// );
+/// ```
+pub fn lagrange_interpolation_at<Z: ModIntBase>(values: &[Z], x: Z) -> Z {
+    let n = values.len();
+    if (x.val() as usize) < n {
+        return values[x.val() as usize];
+    }
+    // x is not a sampling point, so x - j != 0 for all j < n.
+    let (_, ifact) = factorials::<Z>(n);
+    let mut suf = vec![Z::new(1); n + 1];
+    for j in (0..n).rev() {
+        suf[j] = suf[j + 1] * (x - Z::new(j));
+    }
+    let mut pre = Z::new(1);
+    let mut ret = Z::new(0);
+    for i in 0..n {
+        let y = values[i] * pre * suf[i + 1] * ifact[i] * ifact[n - 1 - i];
+        if (n - 1 - i) % 2 == 0 {
+            ret += y;
+        } else {
+            ret -= y;
+        }
+        pre *= x - Z::new(i);
+    }
+    ret
+}
+
+/// Returns $\sum_{i = 0}^{n - 1} i^k$, where $0^0 = 1$.
+///
+/// The sum is a polynomial of degree $k + 1$ in $n$, so it is computed by [`lagrange_interpolation_at`] from the values at $n = 0, 1, \ldots, k + 1$.
+///
+/// # Constraints
+///
+/// - $k + 2 \leq$ the modulus, which is a prime
+///
+/// # Complexity
+///
+/// - $O(k \log k)$
+///
+/// # Example
+///
+/// ```
+/// use ac_library::{poly::sum_of_powers, ModInt998244353 as Mint};
+///
+/// // 0^2 + 1^2 + 2^2 + 3^2
+/// assert_eq!(sum_of_powers::<Mint>(4, 2), Mint::new(14));
+/// ```
+///
+/// [`lagrange_interpolation_at`]: ./fn.lagrange_interpolation_at.html
+pub fn sum_of_powers<Z: ModIntBase>(n: u64, k: usize) -> Z {
+    let mut values = Vec::with_capacity(k + 2);
+    let mut acc = Z::new(0);
+    values.push(acc);
+    for i in 0..=k {
+        acc += Z::new(i).pow(k as u64);
+        values.push(acc);
+    }
+    lagrange_interpolation_at(&values, Z::new(n))
+}
+
+/// Returns the Bernoulli numbers $B_0, B_1, \ldots, B_n$, where $B_1 = -1/2$.
+///
+/// They are the coefficients of the exponential generating function $\frac{x}{e^x - 1}$.
+///
+/// # Constraints
+///
+/// - $n + 2 \leq$ `M::VALUE`, which is a prime
+/// - `M::VALUE` satisfies the constraints of [`convolution`] for the length $2(n + 1)$.
+///
+/// # Complexity
+///
+/// - $O(n \log n)$
+///
+/// # Example
+///
+/// ```
+/// use ac_library::{poly::bernoulli, ModInt998244353 as Mint};
+///
+/// let b: Vec<Mint> = bernoulli(4);
+/// assert_eq!(b, [Mint::new(1), -Mint::new(1) / 2, Mint::new(1) / 6, Mint::new(0), -Mint::new(1) / 30]);
+/// ```
+///
+/// [`convolution`]: ../convolution/fn.convolution.html
+pub fn bernoulli<M: Modulus>(n: usize) -> Vec<StaticModInt<M>> {
+    let (fact, ifact) = factorials::<StaticModInt<M>>(n + 2);
+    // (e^x - 1) / x
+    let mut b = inv_series(&ifact[1..], n + 1);
+    for (b, &f) in b.iter_mut().zip(&fact) {
+        *b *= f;
+    }
+    b
+}
+
+/// Returns the (signed) Stirling numbers of the first kind $s(n, 0), s(n, 1), \ldots, s(n, n)$.
+///
+/// They are defined by $x (x - 1) \cdots (x - n + 1) = \sum_k s(n, k) x^k$, and $(-1)^{n - k} s(n, k)$ is the number of permutations of $n$ elements with $k$ cycles.
+///
+/// # Constraints
+///
+/// - $n <$ `M::VALUE`, which is a prime
+/// - `M::VALUE` satisfies the constraints of [`convolution`] for the length $2(n + 1)$.
+///
+/// # Complexity
+///
+/// - $O(n \log n)$
+///
+/// # Example
+///
+/// ```
+/// use ac_library::{poly::stirling1_row, ModInt998244353 as Mint};
+///
+/// // x(x - 1)(x - 2) = 2x - 3x^2 + x^3
+/// assert_eq!(stirling1_row(3), [Mint::new(0), Mint::new(2), -Mint::new(3), Mint::new(1)]);
+/// ```
+///
+/// [`convolution`]: ../convolution/fn.convolution.html
+pub fn stirling1_row<M: Modulus>(n: usize) -> Vec<StaticModInt<M>> {
+    // f = x (x + 1) ... (x + m - 1), built from the top bit of n
+    let mut f = vec![StaticModInt::new(1)];
+    let mut m = 0;
+    for i in (0..usize::BITS - n.leading_zeros()).rev() {
+        // f(x) f(x + m)
+        let g = taylor_shift(&f, StaticModInt::new(m));
+        f = convolution(&f, &g);
+        m *= 2;
+        if n >> i & 1 == 1 {
+            // f(x) (x + m)
+            f.push(StaticModInt::new(0));
+            for j in (0..m + 2).rev() {
+                let lower = if j > 0 {
+                    f[j - 1]
+                } else {
+                    StaticModInt::new(0)
+                };
+                f[j] = f[j] * StaticModInt::new(m) + lower;
+            }
+            m += 1;
+        }
+    }
+    for (k, c) in f.iter_mut().enumerate() {
+        if (n - k) % 2 == 1 {
+            *c = -*c;
+        }
+    }
+    f
+}
+
+/// Returns the (signed) Stirling numbers of the first kind $s(0, k), s(1, k), \ldots, s(n, k)$.
+///
+/// They are the coefficients of the exponential generating function $\frac{\log(1 + x)^k}{k!}$.
+///
+/// # Constraints
+///
+/// - $n + 1 <$ `M::VALUE`, which is a prime
+/// - `M::VALUE` satisfies the constraints of [`convolution`] for the length $2(n + 1)$.
+///
+/// # Complexity
+///
+/// - $O(n \log n)$
+///
+/// # Example
+///
+/// ```
+/// use ac_library::{poly::stirling1_column, ModInt998244353 as Mint};
+///
+/// let s: Vec<Mint> = stirling1_column(4, 2);
+/// assert_eq!(s, [Mint::new(0), Mint::new(0), Mint::new(1), -Mint::new(3), Mint::new(11)]);
+/// ```
+///
+/// [`convolution`]: ../convolution/fn.convolution.html
+pub fn stirling1_column<M: Modulus>(n: usize, k: usize) -> Vec<StaticModInt<M>> {
+    let mut ret = vec![StaticModInt::new(0); n + 1];
+    if k > n {
+        return ret;
+    }
+    let (fact, ifact) = factorials::<StaticModInt<M>>(n + 2);
+    // log(1 + x) / x = \sum_i (-1)^i x^i / (i + 1)
+    let g = (0..n - k + 1)
+        .map(|i| {
+            let x = ifact[i + 1] * fact[i];
+            if i % 2 == 0 {
+                x
+            } else {
+                -x
+            }
+        })
+        .collect::<Vec<_>>();
+    let g = pow_series(&g, k as u64, n - k + 1);
+    for (i, &c) in g.iter().enumerate() {
+        ret[i + k] = c * ifact[k] * fact[i + k];
+    }
+    ret
+}
+
+/// Returns the Stirling numbers of the second kind $S(n, 0), S(n, 1), \ldots, S(n, n)$.
+///
+/// $S(n, k)$ is the number of ways to partition $n$ elements into $k$ non-empty subsets.
+///
+/// # Constraints
+///
+/// - $n <$ `M::VALUE`, which is a prime
+/// - `M::VALUE` satisfies the constraints of [`convolution`] for the length $2(n + 1)$.
+///
+/// # Complexity
+///
+/// - $O(n \log n)$
+///
+/// # Example
+///
+/// ```
+/// use ac_library::{poly::stirling2_row, ModInt998244353 as Mint};
+///
+/// let s: Vec<Mint> = stirling2_row(4);
+/// assert_eq!(s, [0, 1, 7, 6, 1].iter().map(|&x| Mint::new(x)).collect::<Vec<_>>());
+/// ```
+///
+/// [`convolution`]: ../convolution/fn.convolution.html
+pub fn stirling2_row<M: Modulus>(n: usize) -> Vec<StaticModInt<M>> {
+    let (_, ifact) = factorials::<StaticModInt<M>>(n + 1);
+    // S(n, k) = \sum_i i^n / i! * (-1)^{k - i} / (k - i)!
+    let a = (0..=n)
+        .map(|i| StaticModInt::new(i).pow(n as u64) * ifact[i])
+        .collect::<Vec<_>>();
+    let b = (0..=n)
+        .map(|i| if i % 2 == 0 { ifact[i] } else { -ifact[i] })
+        .collect::<Vec<_>>();
+    let mut s = convolution(&a, &b);
+    s.truncate(n + 1);
+    s
+}
+
+/// Returns the Stirling numbers of the second kind $S(0, k), S(1, k), \ldots, S(n, k)$.
+///
+/// They are the coefficients of the exponential generating function $\frac{(e^x - 1)^k}{k!}$.
+///
+/// # Constraints
+///
+/// - $n + 1 <$ `M::VALUE`, which is a prime
+/// - `M::VALUE` satisfies the constraints of [`convolution`] for the length $2(n + 1)$.
+///
+/// # Complexity
+///
+/// - $O(n \log n)$
+///
+/// # Example
+///
+/// ```
+/// use ac_library::{poly::stirling2_column, ModInt998244353 as Mint};
+///
+/// let s: Vec<Mint> = stirling2_column(4, 2);
+/// assert_eq!(s, [0, 0, 1, 3, 7].iter().map(|&x| Mint::new(x)).collect::<Vec<_>>());
+/// ```
+///
+/// [`convolution`]: ../convolution/fn.convolution.html
+pub fn stirling2_column<M: Modulus>(n: usize, k: usize) -> Vec<StaticModInt<M>> {
+    let mut ret = vec![StaticModInt::new(0); n + 1];
+    if k > n {
+        return ret;
+    }
+    let (fact, ifact) = factorials::<StaticModInt<M>>(n + 2);
+    // (e^x - 1) / x
+    let g = pow_series(&ifact[1..n - k + 2], k as u64, n - k + 1);
+    for (i, &c) in g.iter().enumerate() {
+        ret[i + k] = c * ifact[k] * fact[i + k];
+    }
+    ret
+}
+
+/// Returns the partition numbers $p(0), p(1), \ldots, p(n)$.
+///
+/// $p(n)$ is the number of ways to write $n$ as a sum of positive integers, regardless of the order.
+/// It is computed as $\prod_{i \geq 1} (1 - x^i)^{-1}$ by the pentagonal number theorem.
+///
+/// # Constraints
+///
+/// - `M::VALUE` is a prime and satisfies the constraints of [`convolution`] for the length $2(n + 1)$.
+///
+/// # Complexity
+///
+/// - $O(n \log n)$
+///
+/// # Example
+///
+/// ```
+/// use ac_library::{poly::partition_numbers, ModInt998244353 as Mint};
+///
+/// let p: Vec<Mint> = partition_numbers(7);
+/// assert_eq!(p, [1, 1, 2, 3, 5, 7, 11, 15].iter().map(|&x| Mint::new(x)).collect::<Vec<_>>());
+/// ```
+///
+/// [`convolution`]: ../convolution/fn.convolution.html
+pub fn partition_numbers<M: Modulus>(n: usize) -> Vec<StaticModInt<M>> {
+    // \prod_{i >= 1} (1 - x^i) = \sum_k (-1)^k x^{k(3k - 1)/2}, k in Z
+    let mut f = vec![StaticModInt::new(0); n + 1];
+    f[0] = StaticModInt::new(1);
+    for k in 1.. {
+        let c = if k % 2 == 0 {
+            StaticModInt::new(1)
+        } else {
+            -StaticModInt::new(1)
+        };
+        let i = k * (3 * k - 1) / 2;
+        if i > n {
+            break;
+        }
+        f[i] += c;
+        if i + k <= n {
+            f[i + k] += c;
+        }
+    }
+    inv_series(&f, n + 1)
+}
+
+/// Returns $(0!, 1!, \ldots, (n - 1)!)$ and their inverses.
+fn factorials<Z: ModIntBase>(n: usize) -> (Vec<Z>, Vec<Z>) {
+    let mut fact = vec![Z::new(1); n];
+    for i in 1..n {
+        fact[i] = fact[i - 1] * Z::new(i);
+    }
+    let mut ifact = vec![Z::new(1); n];
+    if n > 0 {
+        ifact[n - 1] = fact[n - 1].inv();
+    }
+    for i in (1..n).rev() {
+        ifact[i - 1] = ifact[i] * Z::new(i);
+    }
+    (fact, ifact)
+}
+
+/// Returns the inverses of `xs`, where the inverse of $0$ is regarded as $0$.
+fn inv_or_zero<Z: ModIntBase>(xs: &[Z]) -> Vec<Z> {
+    let mut pre = Vec::with_capacity(xs.len() + 1);
+    pre.push(Z::new(1));
+    for &x in xs {
+        let last = *pre.last().unwrap();
+        pre.push(if x == Z::new(0) { last } else { last * x });
+    }
+    let mut acc = pre[xs.len()].inv();
+    let mut ret = vec![Z::new(0); xs.len()];
+    for i in (0..xs.len()).rev() {
+        if xs[i] != Z::new(0) {
+            ret[i] = acc * pre[i];
+            acc *= xs[i];
+        }
+    }
+    ret
+}
+
+fn eval<M: Modulus>(f: &[StaticModInt<M>], x: StaticModInt<M>) -> StaticModInt<M> {
+    f.iter()
+        .rev()
+        .fold(StaticModInt::raw(0), |acc, &c| acc * x + c)
+}
+
+/// Returns $f(x + c)$.
+fn taylor_shift<M: Modulus>(f: &[StaticModInt<M>], c: StaticModInt<M>) -> Vec<StaticModInt<M>> {
+    let n = f.len();
+    let (fact, ifact) = factorials::<StaticModInt<M>>(n);
+    // g_j = 1 / j! \sum_i f_i i! c^{i - j} / (i - j)!
+    let a = f
+        .iter()
+        .zip(&fact)
+        .rev()
+        .map(|(&x, &y)| x * y)
+        .collect::<Vec<_>>();
+    let mut b = Vec::with_capacity(n);
+    let mut pw = StaticModInt::new(1);
+    for &y in &ifact {
+        b.push(pw * y);
+        pw *= c;
+    }
+    let g = convolution(&a, &b);
+    (0..n).map(|j| g[n - 1 - j] * ifact[j]).collect()
+}
+
+/// Returns $f^{-1} \bmod x^n$ by the Newton's method.
+///
+/// `f[0]` must be invertible.
+fn inv_series<M: Modulus>(f: &[StaticModInt<M>], n: usize) -> Vec<StaticModInt<M>> {
+    let mut g = vec![f[0].inv()];
+    let mut m = 1;
+    while m < n {
+        m *= 2;
+        // g <- g (2 - f g)
+        let mut h = convolution(&f[..f.len().min(m)], &g);
+        h.truncate(m);
+        for x in &mut h {
+            *x = -*x;
+        }
+        h[0] += StaticModInt::new(2);
+        g = convolution(&g, &h);
+        g.truncate(m);
+    }
+    g.truncate(n);
+    g
+}
+
+/// Returns $\log f \bmod x^n$, where $f_0 = 1$.
+fn log_series<M: Modulus>(f: &[StaticModInt<M>], n: usize) -> Vec<StaticModInt<M>> {
+    // \int f' / f
+    let df = f
+        .iter()
+        .enumerate()
+        .skip(1)
+        .take(n)
+        .map(|(i, &x)| x * StaticModInt::new(i))
+        .collect::<Vec<_>>();
+    let mut g = convolution(&df, &inv_series(f, n));
+    g.resize(n, StaticModInt::new(0));
+    let inv = inv_or_zero(&(0..n).map(StaticModInt::new).collect::<Vec<_>>());
+    let mut ret = vec![StaticModInt::new(0); n];
+    for i in 1..n {
+        ret[i] = g[i - 1] * inv[i];
+    }
+    ret
+}
+
+/// Returns $\exp f \bmod x^n$ by the Newton's method, where $f_0 = 0$.
+fn exp_series<M: Modulus>(f: &[StaticModInt<M>], n: usize) -> Vec<StaticModInt<M>> {
+    let mut g = vec![StaticModInt::new(1)];
+    let mut m = 1;
+    while m < n {
+        m *= 2;
+        // g <- g (1 - log g + f)
+        let mut h = log_series(&g, m);
+        for (i, x) in h.iter_mut().enumerate() {
+            *x = f.get(i).copied().unwrap_or_default() - *x;
+        }
+        h[0] += StaticModInt::new(1);
+        g = convolution(&g, &h);
+        g.truncate(m);
+    }
+    g.truncate(n);
+    g
+}
+
+/// Returns $f^k \bmod x^n$, where $f_0 = 1$.
+fn pow_series<M: Modulus>(f: &[StaticModInt<M>], k: u64, n: usize) -> Vec<StaticModInt<M>> {
+    let mut g = log_series(f, n);
+    for x in &mut g {
+        *x *= StaticModInt::new(k);
+    }
+    exp_series(&g, n)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modint::{ButterflyCache, Mod998244353, ModInt, ModInt998244353 as Mint};
+    use rand::Rng as _;
+    use std::{cell::RefCell, thread::LocalKey};
+
+    fn gen_values(n: usize) -> Vec<Mint> {
+        let mut rng = rand::thread_rng();
+        (0..n)
+            .map(|_| rng.gen_range(0, Mod998244353::VALUE).into())
+            .collect()
+    }
+
+    fn chirp_z_naive(f: &[Mint], a: Mint, r: Mint, m: usize) -> Vec<Mint> {
+        let mut x = a;
+        (0..m)
+            .map(|_| {
+                let y = eval(f, x);
+                x *= r;
+                y
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_chirp_z() {
+        let mut rng = rand::thread_rng();
+        for &(n, m) in &[
+            (0, 3),
+            (3, 0),
+            (1, 1),
+            (1, 5),
+            (5, 1),
+            (10, 20),
+            (100, 77),
+            (200, 300),
+        ] {
+            let f = gen_values(n);
+            let a = Mint::new(rng.gen::<u32>());
+            let r = Mint::new(rng.gen::<u32>());
+            assert_eq!(chirp_z(&f, a, r, m), chirp_z_naive(&f, a, r, m));
+        }
+    }
+
+    fn eval_values(f: &[Mint], xs: impl Iterator<Item = u64>) -> Vec<Mint> {
+        xs.map(|x| eval(f, Mint::new(x))).collect()
+    }
+
+    #[test]
+    fn test_shift_of_sampling_points() {
+        let mut rng = rand::thread_rng();
+        for &(n, m) in &[(0, 3), (3, 0), (1, 1), (1, 4), (5, 5), (30, 70), (100, 40)] {
+            let f = gen_values(n);
+            let values = eval_values(&f, 0..n as u64);
+            for c in (0..2 * n as u64 + 2).chain(vec![rng.gen::<u32>() as u64]) {
+                assert_eq!(
+                    shift_of_sampling_points(&values, Mint::new(c), m),
+                    eval_values(&f, c..c + m as u64),
+                );
+            }
+            // c - (n - 1) wraps around the modulus
+            let c = Mod998244353::VALUE as u64 - 3;
+            assert_eq!(
+                shift_of_sampling_points(&values, Mint::new(c), m),
+                eval_values(&f, c..c + m as u64),
+            );
+        }
+    }
+
+    #[test]
+    fn test_shift_of_sampling_points_small_modulus() {
+        // m + n > p, so some of the points coincide with the sampling points modulo p
+        #[derive(Copy, Clone, Eq, PartialEq)]
+        enum M17 {}
+        impl Modulus for M17 {
+            const VALUE: u32 = 17;
+            const HINT_VALUE_IS_PRIME: bool = true;
+
+            fn butterfly_cache() -> &'static LocalKey<RefCell<Option<ButterflyCache<Self>>>> {
+                thread_local! {
+                    static BUTTERFLY_CACHE: RefCell<Option<ButterflyCache<M17>>> = RefCell::default();
+                }
+                &BUTTERFLY_CACHE
+            }
+        }
+        type Mint17 = StaticModInt<M17>;
+        let f = [3, 1, 4, 1, 5].map(Mint17::new);
+        let values = (0..5).map(|x| eval(&f, Mint17::new(x))).collect::<Vec<_>>();
+        for c in 0..17 {
+            let expected = (c..c + 40)
+                .map(|x| eval(&f, Mint17::new(x)))
+                .collect::<Vec<_>>();
+            assert_eq!(
+                shift_of_sampling_points(&values, Mint17::new(c), 40),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_lagrange_interpolation_at() {
+        let mut rng = rand::thread_rng();
+        for n in 0..30 {
+            let f = gen_values(n);
+            let values = eval_values(&f, 0..n as u64);
+            for x in (0..n as u64 + 3).chain(vec![rng.gen::<u32>() as u64]) {
+                assert_eq!(
+                    lagrange_interpolation_at(&values, Mint::new(x)),
+                    eval(&f, Mint::new(x))
+                );
+            }
+        }
+        let values = [1, 2, 3].map(ModInt::new);
+        assert_eq!(
+            lagrange_interpolation_at(&values, ModInt::new(100)),
+            ModInt::new(101)
+        );
+    }
+
+    #[test]
+    fn test_chirp_z_degenerate() {
+        let f = gen_values(50);
+        let a = Mint::new(12345);
+        // r = 0
+        assert_eq!(
+            chirp_z(&f, a, Mint::new(0), 10),
+            chirp_z_naive(&f, a, Mint::new(0), 10)
+        );
+        // a = 0
+        assert_eq!(
+            chirp_z(&f, Mint::new(0), Mint::new(3), 10),
+            chirp_z_naive(&f, Mint::new(0), Mint::new(3), 10)
+        );
+        // r of small orders
+        for &order in &[1, 2, 4, 7, 17] {
+            let r = Mint::new(3).pow((Mod998244353::VALUE as u64 - 1) / order);
+            assert_eq!(r.pow(order), Mint::new(1));
+            assert_eq!(chirp_z(&f, a, r, 100), chirp_z_naive(&f, a, r, 100));
+        }
+    }
+
+    #[test]
+    fn test_sum_of_powers() {
+        for n in 0..30u64 {
+            for k in 0..10 {
+                let expected = (0..n).map(|i| Mint::new(i).pow(k as u64)).sum::<Mint>();
+                assert_eq!(sum_of_powers::<Mint>(n, k), expected);
+            }
+        }
+        // \sum_{i < n} i^3 = (n (n - 1) / 2)^2
+        let n = 1_000_000_000_000_000_000u64;
+        let t = Mint::new(n) * Mint::new(n - 1) / Mint::new(2);
+        assert_eq!(sum_of_powers::<Mint>(n, 3), t * t);
+        let k = 100_000;
+        let expected = (0..k as u64 + 5)
+            .map(|i| Mint::new(i).pow(k as u64))
+            .sum::<Mint>();
+        assert_eq!(sum_of_powers::<Mint>(k as u64 + 5, k), expected);
+    }
+
+    /// Returns the table of s(i, j) and S(i, j) for i, j <= n.
+    fn stirling_naive(n: usize) -> (Vec<Vec<Mint>>, Vec<Vec<Mint>>) {
+        let mut s1 = vec![vec![Mint::new(0); n + 1]; n + 1];
+        let mut s2 = vec![vec![Mint::new(0); n + 1]; n + 1];
+        s1[0][0] = Mint::new(1);
+        s2[0][0] = Mint::new(1);
+        for i in 1..=n {
+            for j in 1..=i {
+                s1[i][j] = s1[i - 1][j - 1] - Mint::new(i - 1) * s1[i - 1][j];
+                s2[i][j] = s2[i - 1][j - 1] + Mint::new(j) * s2[i - 1][j];
+            }
+        }
+        (s1, s2)
+    }
+
+    #[test]
+    fn test_stirling() {
+        let n = 40;
+        let (s1, s2) = stirling_naive(n);
+        for i in 0..=n {
+            assert_eq!(stirling1_row::<Mod998244353>(i), s1[i][..=i]);
+            assert_eq!(stirling2_row::<Mod998244353>(i), s2[i][..=i]);
+        }
+        for k in 0..=n + 1 {
+            for m in (0..=n).step_by(7) {
+                let column1 = (0..=m)
+                    .map(|i| s1[i].get(k).copied().unwrap_or_default())
+                    .collect::<Vec<_>>();
+                let column2 = (0..=m)
+                    .map(|i| s2[i].get(k).copied().unwrap_or_default())
+                    .collect::<Vec<_>>();
+                assert_eq!(stirling1_column::<Mod998244353>(m, k), column1);
+                assert_eq!(stirling2_column::<Mod998244353>(m, k), column2);
+            }
+        }
+        // \sum_k |s(n, k)| = n!
+        let n = 1000;
+        let row = stirling1_row::<Mod998244353>(n);
+        let sum = row
+            .iter()
+            .enumerate()
+            .map(|(k, &x)| if (n - k) % 2 == 0 { x } else { -x })
+            .sum::<Mint>();
+        assert_eq!(sum, (1..=n).map(Mint::new).product());
+    }
+
+    #[test]
+    fn test_bernoulli() {
+        let n = 50;
+        let b = bernoulli::<Mod998244353>(n);
+        assert_eq!(b.len(), n + 1);
+        // \sum_{j = 0}^{m} \binom{m + 1}{j} B_j = [m = 0]
+        let (fact, ifact) = factorials::<Mint>(n + 2);
+        for m in 0..=n {
+            let sum = (0..=m)
+                .map(|j| fact[m + 1] * ifact[j] * ifact[m + 1 - j] * b[j])
+                .sum::<Mint>();
+            assert_eq!(sum, Mint::new((m == 0) as u32));
+        }
+        assert_eq!(b[12], Mint::new(-691) / Mint::new(2730));
+        assert!(b[3..].iter().step_by(2).all(|&x| x == Mint::new(0)));
+        // Faulhaber's formula: \sum_{i < n} i^k = 1 / (k + 1) \sum_j \binom{k + 1}{j} B_j n^{k + 1 - j}
+        let (x, k) = (Mint::new(123_456_789), 10);
+        let expected = (0..=k)
+            .map(|j| fact[k + 1] * ifact[j] * ifact[k + 1 - j] * b[j] * x.pow((k + 1 - j) as u64))
+            .sum::<Mint>()
+            / Mint::new(k + 1);
+        assert_eq!(sum_of_powers::<Mint>(123_456_789, k), expected);
+    }
+
+    #[test]
+    fn test_partition_numbers() {
+        let n = 300;
+        let mut dp = vec![Mint::new(0); n + 1];
+        dp[0] = Mint::new(1);
+        for i in 1..=n {
+            for j in i..=n {
+                let x = dp[j - i];
+                dp[j] += x;
+            }
+        }
+        assert_eq!(partition_numbers::<Mod998244353>(n), dp);
+        assert_eq!(partition_numbers::<Mod998244353>(0), [Mint::new(1)]);
+        assert_eq!(partition_numbers::<Mod998244353>(1), [Mint::new(1); 2]);
+        // p(100) = 190569292
+        assert_eq!(
+            partition_numbers::<Mod998244353>(100)[100],
+            Mint::new(190_569_292)
+        );
+    }
+}
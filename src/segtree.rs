@@ -1,10 +1,10 @@
 use crate::internal_bit::ceil_pow2;
 use crate::internal_type_traits::{BoundedAbove, BoundedBelow, One, Zero};
-use std::cmp::{max, min};
+use std::cmp::{max, min, Ordering};
 use std::convert::Infallible;
 use std::iter::FromIterator;
 use std::marker::PhantomData;
-use std::ops::{Add, BitAnd, BitOr, BitXor, Bound, Mul, Not, RangeBounds};
+use std::ops::{Add, BitAnd, BitOr, BitXor, Bound, Mul, Not, RangeBounds, Rem};
 
 // TODO Should I split monoid-related traits to another module?
 pub trait Monoid {
@@ -111,6 +111,137 @@ where
     }
 }
 
+/// The product of two monoids, whose operation is taken componentwise.
+impl<M1: Monoid, M2: Monoid> Monoid for (M1, M2) {
+    type S = (M1::S, M2::S);
+    fn identity() -> Self::S {
+        (M1::identity(), M2::identity())
+    }
+    fn binary_operation(a: &Self::S, b: &Self::S) -> Self::S {
+        (
+            M1::binary_operation(&a.0, &b.0),
+            M2::binary_operation(&a.1, &b.1),
+        )
+    }
+}
+
+/// The opposite monoid of `M`, i.e. $a \cdot b$ is `M::binary_operation(b, a)`.
+pub struct Reversed<M>(Infallible, PhantomData<fn() -> M>);
+impl<M: Monoid> Monoid for Reversed<M> {
+    type S = M::S;
+    fn identity() -> Self::S {
+        M::identity()
+    }
+    fn binary_operation(a: &Self::S, b: &Self::S) -> Self::S {
+        M::binary_operation(b, a)
+    }
+}
+
+/// The maximum value and its leftmost index, as `(value, index)`.
+///
+/// The identity is `(S::min_value(), usize::MAX)`.
+pub struct ArgMax<S>(Infallible, PhantomData<fn() -> S>);
+impl<S> Monoid for ArgMax<S>
+where
+    S: Copy + Ord + BoundedBelow,
+{
+    type S = (S, usize);
+    fn identity() -> Self::S {
+        (S::min_value(), usize::MAX)
+    }
+    fn binary_operation(a: &Self::S, b: &Self::S) -> Self::S {
+        match a.0.cmp(&b.0) {
+            Ordering::Greater => *a,
+            Ordering::Less => *b,
+            Ordering::Equal => min(*a, *b),
+        }
+    }
+}
+
+/// The minimum value and its leftmost index, as `(value, index)`.
+///
+/// The identity is `(S::max_value(), usize::MAX)`.
+pub struct ArgMin<S>(Infallible, PhantomData<fn() -> S>);
+impl<S> Monoid for ArgMin<S>
+where
+    S: Copy + Ord + BoundedAbove,
+{
+    type S = (S, usize);
+    fn identity() -> Self::S {
+        (S::max_value(), usize::MAX)
+    }
+    fn binary_operation(a: &Self::S, b: &Self::S) -> Self::S {
+        min(*a, *b)
+    }
+}
+
+/// The greatest common divisor of non-negative integers, with $\gcd(0, 0) = 0$.
+pub struct Gcd<S>(Infallible, PhantomData<fn() -> S>);
+impl<S> Monoid for Gcd<S>
+where
+    S: Copy + Eq + Rem<Output = S> + Zero,
+{
+    type S = S;
+    fn identity() -> Self::S {
+        S::zero()
+    }
+    fn binary_operation(a: &Self::S, b: &Self::S) -> Self::S {
+        let (mut a, mut b) = (*a, *b);
+        while b != S::zero() {
+            let r = a % b;
+            a = b;
+            b = r;
+        }
+        a
+    }
+}
+
+/// The composition of affine maps $x \mapsto ax + b$, represented as `(a, b)`.
+///
+/// $f \cdot g$ is the map that applies $f$ first and then $g$, so `prod(l..r)` applies the maps from left to right.
+///
+/// `S` can be any type with `+`, `*`, `0` and `1`, such as the integer types and the modint types.
+pub struct Affine<S>(Infallible, PhantomData<fn() -> S>);
+impl<S> Monoid for Affine<S>
+where
+    S: Copy + Add<Output = S> + Mul<Output = S> + Zero + One,
+{
+    type S = (S, S);
+    fn identity() -> Self::S {
+        (S::one(), S::zero())
+    }
+    fn binary_operation(f: &Self::S, g: &Self::S) -> Self::S {
+        (g.0 * f.0, g.0 * f.1 + g.1)
+    }
+}
+
+/// The product of $N \times N$ matrices.
+pub struct MatrixProduct<S, const N: usize>(Infallible, PhantomData<fn() -> S>);
+impl<S, const N: usize> Monoid for MatrixProduct<S, N>
+where
+    S: Copy + Add<Output = S> + Mul<Output = S> + Zero + One,
+{
+    type S = [[S; N]; N];
+    fn identity() -> Self::S {
+        let mut e = [[S::zero(); N]; N];
+        for (i, row) in e.iter_mut().enumerate() {
+            row[i] = S::one();
+        }
+        e
+    }
+    fn binary_operation(a: &Self::S, b: &Self::S) -> Self::S {
+        let mut c = [[S::zero(); N]; N];
+        for (ci, ai) in c.iter_mut().zip(a) {
+            for (&aik, bk) in ai.iter().zip(b) {
+                for (cij, &bkj) in ci.iter_mut().zip(bk) {
+                    *cij = *cij + aik * bkj;
+                }
+            }
+        }
+        c
+    }
+}
+
 impl<M: Monoid> Default for Segtree<M> {
     fn default() -> Self {
         Segtree::new(0)
@@ -312,7 +443,8 @@ where
 
 #[cfg(test)]
 mod tests {
-    use crate::segtree::Max;
+    use crate::modint::ModInt998244353;
+    use crate::segtree::{Additive, Affine, ArgMax, ArgMin, Gcd, MatrixProduct, Max, Reversed};
     use crate::Segtree;
     use std::ops::{Bound::*, RangeBounds};
 
@@ -352,6 +484,82 @@ mod tests {
         check_segtree(&base, &segtree);
     }
 
+    #[test]
+    fn test_monoid_combinators() {
+        let base = vec![3, 1, 4, 1, 5, 9, 2, 6, 5, 3];
+        let n = base.len();
+        let pairs = base.iter().enumerate().map(|(i, &x)| (x, i));
+
+        let argmax: Segtree<ArgMax<i32>> = pairs.clone().collect();
+        let argmin: Segtree<ArgMin<i32>> = pairs.collect();
+        let sum_max: Segtree<(Additive<i32>, Max<i32>)> = base.iter().map(|&x| (x, x)).collect();
+        let gcd: Segtree<Gcd<i32>> = base.iter().map(|&x| 6 * x).collect();
+        for l in 0..=n {
+            for r in l..=n {
+                let s = &base[l..r];
+                let max = s.iter().copied().max();
+                let min = s.iter().copied().min();
+                let argmax_expected = max.map_or((i32::MIN, usize::MAX), |m| {
+                    (m, l + s.iter().position(|&x| x == m).unwrap())
+                });
+                let argmin_expected = min.map_or((i32::MAX, usize::MAX), |m| {
+                    (m, l + s.iter().position(|&x| x == m).unwrap())
+                });
+                assert_eq!(argmax.prod(l..r), argmax_expected);
+                assert_eq!(argmin.prod(l..r), argmin_expected);
+                assert_eq!(
+                    sum_max.prod(l..r),
+                    (s.iter().sum(), max.unwrap_or(i32::MIN))
+                );
+                let g = s.iter().fold(0, |a, &x| {
+                    let (mut a, mut b) = (a, 6 * x);
+                    while b != 0 {
+                        a %= b;
+                        std::mem::swap(&mut a, &mut b);
+                    }
+                    a
+                });
+                assert_eq!(gcd.prod(l..r), g);
+            }
+        }
+    }
+
+    #[test]
+    fn test_non_commutative_monoids() {
+        type Z = ModInt998244353;
+        let maps = (1..=6)
+            .map(|i| (Z::new(i), Z::new(10 * i)))
+            .collect::<Vec<_>>();
+        let affine: Segtree<Affine<Z>> = maps.iter().copied().collect();
+        let reversed: Segtree<Reversed<Affine<Z>>> = maps.iter().copied().collect();
+        let matrices: Segtree<MatrixProduct<Z, 2>> = maps
+            .iter()
+            .map(|&(a, b)| [[a, Z::new(0)], [b, Z::new(1)]])
+            .collect();
+        for l in 0..=maps.len() {
+            for r in l..=maps.len() {
+                let x = Z::new(7);
+                let forward = maps[l..r].iter().fold(x, |x, &(a, b)| a * x + b);
+                let backward = maps[l..r].iter().rev().fold(x, |x, &(a, b)| a * x + b);
+                let (a, b) = affine.prod(l..r);
+                assert_eq!(a * x + b, forward);
+                let (a, b) = reversed.prod(l..r);
+                assert_eq!(a * x + b, backward);
+                // The row vector (x, 1) times the matrices.
+                let m = matrices.prod(l..r);
+                assert_eq!(x * m[0][0] + m[1][0], forward);
+                assert_eq!(x * m[0][1] + m[1][1], Z::new(1));
+            }
+        }
+        let affine_i64: Segtree<Affine<i64>> = vec![(2, 1), (3, 0), (1, 5)].into();
+        // x -> 3(2x + 1) + 5
+        assert_eq!(affine_i64.all_prod(), (6, 8));
+        assert_eq!(
+            Segtree::<MatrixProduct<i64, 3>>::new(5).all_prod(),
+            [[1, 0, 0], [0, 1, 0], [0, 0, 1]]
+        );
+    }
+
     //noinspection DuplicatedCode
     fn check_segtree(base: &[i32], segtree: &Segtree<Max<i32>>) {
         let n = base.len();
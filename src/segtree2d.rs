@@ -0,0 +1,457 @@
+//! Two-dimensional segment trees for point updates and rectangle products.
+//!
+//! Since the product of a rectangle is taken in no particular order, the monoid must be commutative.
+
+use crate::internal_bit::ceil_pow2;
+use crate::segtree::{Monoid, Segtree};
+use std::ops::{Bound, RangeBounds};
+
+/// A segment tree over an $h \times w$ grid.
+///
+/// It uses $4hw$ nodes, where $h$ and $w$ are rounded up to powers of two.
+///
+/// # Example
+///
+/// ```
+/// use ac_library::{Additive, Segtree2D};
+///
+/// let mut segtree = Segtree2D::<Additive<i32>>::new(3, 4);
+/// segtree.set(0, 0, 1);
+/// segtree.set(1, 2, 2);
+/// segtree.set(2, 3, 3);
+/// assert_eq!(segtree.prod(..2, ..), 3);
+/// assert_eq!(segtree.prod(1.., 2..), 5);
+/// assert_eq!(segtree.all_prod(), 6);
+/// ```
+#[derive(Clone)]
+pub struct Segtree2D<M>
+where
+    M: Monoid,
+{
+    h: usize,
+    w: usize,
+    size_h: usize,
+    size_w: usize,
+    log_h: usize,
+    log_w: usize,
+    d: Vec<M::S>,
+}
+
+impl<M: Monoid> Segtree2D<M> {
+    pub fn new(h: usize, w: usize) -> Self {
+        let log_h = ceil_pow2(h as u32) as usize;
+        let log_w = ceil_pow2(w as u32) as usize;
+        let (size_h, size_w) = (1 << log_h, 1 << log_w);
+        Segtree2D {
+            h,
+            w,
+            size_h,
+            size_w,
+            log_h,
+            log_w,
+            d: vec![M::identity(); 4 * size_h * size_w],
+        }
+    }
+}
+impl<M: Monoid> From<Vec<Vec<M::S>>> for Segtree2D<M> {
+    /// # Panics
+    ///
+    /// Panics if the rows have different lengths.
+    fn from(v: Vec<Vec<M::S>>) -> Self {
+        let h = v.len();
+        let w = v.first().map_or(0, Vec::len);
+        assert!(v.iter().all(|row| row.len() == w));
+        let mut ret = Self::new(h, w);
+        let (size_h, size_w) = (ret.size_h, ret.size_w);
+        for (i, row) in v.into_iter().enumerate() {
+            let k = ret.idx(size_h + i, size_w);
+            ret.d[k..k + w].clone_from_slice(&row);
+            for j in (1..size_w).rev() {
+                ret.update_col(size_h + i, j);
+            }
+        }
+        for i in (1..size_h).rev() {
+            for j in 1..2 * size_w {
+                ret.update_row(i, j);
+            }
+        }
+        ret
+    }
+}
+impl<M: Monoid> Segtree2D<M> {
+    /// Sets the $(i, j)$-th element to $x$.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(\log h \log w)$
+    pub fn set(&mut self, i: usize, j: usize, x: M::S) {
+        assert!(i < self.h && j < self.w);
+        let (i, j) = (i + self.size_h, j + self.size_w);
+        let k = self.idx(i, j);
+        self.d[k] = x;
+        for t in 1..=self.log_w {
+            self.update_col(i, j >> t);
+        }
+        for s in 1..=self.log_h {
+            for t in 0..=self.log_w {
+                self.update_row(i >> s, j >> t);
+            }
+        }
+    }
+
+    /// Returns the $(i, j)$-th element.
+    pub fn get(&self, i: usize, j: usize) -> M::S {
+        assert!(i < self.h && j < self.w);
+        self.d[self.idx(i + self.size_h, j + self.size_w)].clone()
+    }
+
+    /// Returns the product of the elements in `rows` $\times$ `cols`.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(\log h \log w)$
+    pub fn prod<R1, R2>(&self, rows: R1, cols: R2) -> M::S
+    where
+        R1: RangeBounds<usize>,
+        R2: RangeBounds<usize>,
+    {
+        let (mut u, mut d) = to_pair(rows, self.h);
+        let (l, r) = to_pair(cols, self.w);
+        let mut sm = M::identity();
+        u += self.size_h;
+        d += self.size_h;
+        while u < d {
+            if u & 1 != 0 {
+                sm = M::binary_operation(&sm, &self.prod_row(u, l, r));
+                u += 1;
+            }
+            if d & 1 != 0 {
+                d -= 1;
+                sm = M::binary_operation(&sm, &self.prod_row(d, l, r));
+            }
+            u >>= 1;
+            d >>= 1;
+        }
+        sm
+    }
+
+    /// Returns the product of all the elements.
+    pub fn all_prod(&self) -> M::S {
+        self.d[self.idx(1, 1)].clone()
+    }
+
+    fn idx(&self, i: usize, j: usize) -> usize {
+        i * 2 * self.size_w + j
+    }
+
+    fn prod_row(&self, i: usize, mut l: usize, mut r: usize) -> M::S {
+        let mut sm = M::identity();
+        l += self.size_w;
+        r += self.size_w;
+        while l < r {
+            if l & 1 != 0 {
+                sm = M::binary_operation(&sm, &self.d[self.idx(i, l)]);
+                l += 1;
+            }
+            if r & 1 != 0 {
+                r -= 1;
+                sm = M::binary_operation(&sm, &self.d[self.idx(i, r)]);
+            }
+            l >>= 1;
+            r >>= 1;
+        }
+        sm
+    }
+
+    fn update_col(&mut self, i: usize, j: usize) {
+        let k = self.idx(i, j);
+        self.d[k] = M::binary_operation(&self.d[k + j], &self.d[k + j + 1]);
+    }
+
+    fn update_row(&mut self, i: usize, j: usize) {
+        let k = self.idx(i, j);
+        self.d[k] =
+            M::binary_operation(&self.d[self.idx(2 * i, j)], &self.d[self.idx(2 * i + 1, j)]);
+    }
+}
+
+/// A two-dimensional segment tree over a set of points given in advance.
+///
+/// Only the given points can hold non-identity values, and the coordinates can be any `Ord` type.
+/// It is a segment tree over the sorted $x$-coordinates, each node of which has a [`Segtree`] over the sorted $y$-coordinates of the points in it.
+/// It uses $O(n \log n)$ space for $n$ points.
+///
+/// # Example
+///
+/// ```
+/// use ac_library::{Additive, CompressedSegtree2D};
+///
+/// let points = [(-1_000_000_000, 5), (0, -3), (3, 5), (3, 1_000_000_000)];
+/// let mut segtree = CompressedSegtree2D::<Additive<i64>>::new(&points);
+/// segtree.set(-1_000_000_000, 5, 1);
+/// segtree.set(3, 5, 2);
+/// segtree.set(3, 1_000_000_000, 4);
+/// assert_eq!(segtree.prod(.., 5..=5), 3);
+/// assert_eq!(segtree.prod(0.., 0..), 6);
+/// ```
+///
+/// [`Segtree`]: ../segtree/struct.Segtree.html
+#[derive(Clone)]
+pub struct CompressedSegtree2D<M, T = i64>
+where
+    M: Monoid,
+    T: Ord + Copy,
+{
+    size: usize,
+    xs: Vec<T>,
+    ys: Vec<Vec<T>>,
+    d: Vec<Segtree<M>>,
+}
+
+impl<M: Monoid, T: Ord + Copy> CompressedSegtree2D<M, T> {
+    /// Creates a tree over `points`, whose values are `M::identity()`.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(n \log n)$
+    pub fn new(points: &[(T, T)]) -> Self {
+        let mut xs = points.iter().map(|&(x, _)| x).collect::<Vec<_>>();
+        xs.sort_unstable();
+        xs.dedup();
+        let size = 1 << ceil_pow2(xs.len() as u32);
+        let mut ys = vec![vec![]; 2 * size];
+        for &(x, y) in points {
+            ys[size + lower_bound(&xs, &x)].push(y);
+        }
+        for i in (1..2 * size).rev() {
+            if i < size {
+                let mut v = ys[2 * i].clone();
+                v.extend_from_slice(&ys[2 * i + 1]);
+                ys[i] = v;
+            }
+            ys[i].sort_unstable();
+            ys[i].dedup();
+        }
+        let d = ys.iter().map(|v| Segtree::new(v.len())).collect();
+        CompressedSegtree2D { size, xs, ys, d }
+    }
+
+    /// Sets the value at $(x, y)$ to $v$.
+    ///
+    /// # Constraints
+    ///
+    /// - $(x, y)$ is one of the given points
+    ///
+    /// # Panics
+    ///
+    /// Panics if the above constraints are not satisfied.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(\log^2 n)$
+    pub fn set(&mut self, x: T, y: T, v: M::S) {
+        let i = self.xs.binary_search(&x).expect("not a given point");
+        let mut k = self.size + i;
+        let j = self.ys[k].binary_search(&y).expect("not a given point");
+        self.d[k].set(j, v);
+        while k > 1 {
+            k >>= 1;
+            let v = M::binary_operation(&self.get_at(2 * k, y), &self.get_at(2 * k + 1, y));
+            let j = lower_bound(&self.ys[k], &y);
+            self.d[k].set(j, v);
+        }
+    }
+
+    /// Returns the value at $(x, y)$, which is `M::identity()` if $(x, y)$ is not a given point.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(\log n)$
+    pub fn get(&self, x: T, y: T) -> M::S {
+        match self.xs.binary_search(&x) {
+            Ok(i) => self.get_at(self.size + i, y),
+            Err(_) => M::identity(),
+        }
+    }
+
+    /// Returns the product of the values at the given points in `xs` $\times$ `ys`.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(\log^2 n)$
+    pub fn prod<R1, R2>(&self, xs: R1, ys: R2) -> M::S
+    where
+        R1: RangeBounds<T>,
+        R2: RangeBounds<T>,
+    {
+        let (mut l, mut r) = to_index_pair(&self.xs, &xs);
+        let mut sm = M::identity();
+        l += self.size;
+        r += self.size;
+        while l < r {
+            if l & 1 != 0 {
+                sm = M::binary_operation(&sm, &self.prod_node(l, &ys));
+                l += 1;
+            }
+            if r & 1 != 0 {
+                r -= 1;
+                sm = M::binary_operation(&sm, &self.prod_node(r, &ys));
+            }
+            l >>= 1;
+            r >>= 1;
+        }
+        sm
+    }
+
+    /// Returns the product of all the values.
+    pub fn all_prod(&self) -> M::S {
+        self.d[1].all_prod()
+    }
+
+    fn get_at(&self, k: usize, y: T) -> M::S {
+        match self.ys[k].binary_search(&y) {
+            Ok(j) => self.d[k].get(j),
+            Err(_) => M::identity(),
+        }
+    }
+
+    fn prod_node<R: RangeBounds<T>>(&self, k: usize, ys: &R) -> M::S {
+        let (l, r) = to_index_pair(&self.ys[k], ys);
+        self.d[k].prod(l..r)
+    }
+}
+
+fn to_pair<R: RangeBounds<usize>>(range: R, n: usize) -> (usize, usize) {
+    let r = match range.end_bound() {
+        Bound::Included(r) => r + 1,
+        Bound::Excluded(r) => *r,
+        Bound::Unbounded => n,
+    };
+    let l = match range.start_bound() {
+        Bound::Included(l) => *l,
+        Bound::Excluded(l) => l + 1,
+        Bound::Unbounded => 0,
+    };
+    assert!(l <= r && r <= n);
+    (l, r)
+}
+
+/// Returns the range of indices of the elements of a sorted slice `v` in `range`.
+fn to_index_pair<T: Ord, R: RangeBounds<T>>(v: &[T], range: &R) -> (usize, usize) {
+    let l = match range.start_bound() {
+        Bound::Included(l) => v.partition_point(|x| x < l),
+        Bound::Excluded(l) => v.partition_point(|x| x <= l),
+        Bound::Unbounded => 0,
+    };
+    let r = match range.end_bound() {
+        Bound::Included(r) => v.partition_point(|x| x <= r),
+        Bound::Excluded(r) => v.partition_point(|x| x < r),
+        Bound::Unbounded => v.len(),
+    };
+    (l, r.max(l))
+}
+
+fn lower_bound<T: Ord>(v: &[T], x: &T) -> usize {
+    v.partition_point(|y| y < x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::segtree::{Additive, Max};
+    use rand::Rng;
+
+    #[test]
+    fn test_segtree2d() {
+        let mut rng = rand::thread_rng();
+        for &(h, w) in &[
+            (0, 0),
+            (0, 3),
+            (3, 0),
+            (1, 1),
+            (1, 5),
+            (4, 1),
+            (5, 7),
+            (8, 8),
+        ] {
+            let init = (0..h)
+                .map(|_| (0..w).map(|_| rng.gen_range(0, 100)).collect::<Vec<i64>>())
+                .collect::<Vec<_>>();
+            let mut naive = init.clone();
+            let (mut sum, mut max) = if h == 0 {
+                (Segtree2D::new(h, w), Segtree2D::new(h, w))
+            } else {
+                (
+                    Segtree2D::<Additive<i64>>::from(init.clone()),
+                    Segtree2D::<Max<i64>>::from(init),
+                )
+            };
+            for _ in 0..300 {
+                if h > 0 && w > 0 && rng.gen() {
+                    let (i, j) = (rng.gen_range(0, h), rng.gen_range(0, w));
+                    let x = rng.gen_range(0, 100);
+                    naive[i][j] = x;
+                    sum.set(i, j, x);
+                    max.set(i, j, x);
+                    assert_eq!(sum.get(i, j), x);
+                }
+                let u = rng.gen_range(0, h + 1);
+                let d = rng.gen_range(u, h + 1);
+                let l = rng.gen_range(0, w + 1);
+                let r = rng.gen_range(l, w + 1);
+                let cells = || naive[u..d].iter().flat_map(|row| row[l..r].iter().copied());
+                assert_eq!(sum.prod(u..d, l..r), cells().sum::<i64>());
+                assert_eq!(max.prod(u..d, l..r), cells().max().unwrap_or(i64::MIN));
+                assert_eq!(sum.all_prod(), naive.iter().flatten().sum::<i64>());
+                if u < d && l < r {
+                    assert_eq!(sum.prod(u..=d - 1, l..=r - 1), cells().sum::<i64>());
+                }
+            }
+        }
+        let segtree = Segtree2D::<Additive<i64>>::new(3, 5);
+        assert_eq!(segtree.prod(.., ..), 0);
+    }
+
+    #[test]
+    fn test_compressed_segtree2d() {
+        let mut rng = rand::thread_rng();
+        for &n in &[0, 1, 2, 10, 100] {
+            let points = (0..n)
+                .map(|_| (rng.gen_range(-10, 10), rng.gen_range(-10, 10)))
+                .collect::<Vec<(i64, i64)>>();
+            let mut segtree = CompressedSegtree2D::<Additive<i64>>::new(&points);
+            let mut naive = std::collections::BTreeMap::new();
+            for _ in 0..300 {
+                if n > 0 && rng.gen() {
+                    let (x, y) = points[rng.gen_range(0, n)];
+                    let v = rng.gen_range(0, 100);
+                    segtree.set(x, y, v);
+                    naive.insert((x, y), v);
+                }
+                let (x, y) = (rng.gen_range(-11, 11), rng.gen_range(-11, 11));
+                assert_eq!(segtree.get(x, y), naive.get(&(x, y)).copied().unwrap_or(0));
+                let xl = rng.gen_range(-11, 11);
+                let xr = rng.gen_range(-11, 11);
+                let yl = rng.gen_range(-11, 11);
+                let yr = rng.gen_range(-11, 11);
+                let expected = naive
+                    .iter()
+                    .filter(|&(&(x, y), _)| xl <= x && x < xr && yl < y && y <= yr)
+                    .map(|(_, &v)| v)
+                    .sum::<i64>();
+                assert_eq!(
+                    segtree.prod(xl..xr, (Bound::Excluded(yl), Bound::Included(yr))),
+                    expected
+                );
+                assert_eq!(segtree.all_prod(), naive.values().sum::<i64>());
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_compressed_segtree2d_unknown_point() {
+        let mut segtree = CompressedSegtree2D::<Additive<i64>>::new(&[(0, 0), (1, 1)]);
+        segtree.set(0, 1, 1);
+    }
+}
@@ -0,0 +1,359 @@
+//! Segment Tree Beats for range chmin, chmax and add with range sum, max and min queries.
+//!
+//! It is a [`LazySegtree`] whose mapping may fail on a node, in which case the tree recurses into the children.
+//! See [`MapMonoid::try_mapping`].
+//!
+//! [`LazySegtree`]: ../lazysegtree/struct.LazySegtree.html
+//! [`MapMonoid::try_mapping`]: ../lazysegtree/trait.MapMonoid.html#method.try_mapping
+
+use crate::internal_type_traits::Integral;
+use crate::lazysegtree::{LazySegtree, MapMonoid};
+use crate::segtree::Monoid;
+use std::convert::Infallible;
+use std::marker::PhantomData;
+
+/// A [`LazySegtree`] supporting range chmin, chmax and add, and range sum, max and min queries.
+///
+/// # Constraints
+///
+/// - The sums and the values must fit in `T`.
+///
+/// # Complexity
+///
+/// - $O(\log^2 n)$ amortized for each `apply_range`
+/// - $O(\log n)$ for each `prod`
+///
+/// # Example
+///
+/// ```
+/// use ac_library::{BeatsMap, BeatsNode, SegtreeBeats};
+///
+/// let mut segtree = SegtreeBeats::<i64>::from(
+///     [3, 1, 4, 1, 5].iter().map(|&x| BeatsNode::new(x)).collect::<Vec<_>>(),
+/// );
+/// segtree.apply_range(.., BeatsMap::chmin(3));
+/// segtree.apply_range(1..4, BeatsMap::add(2));
+/// segtree.apply_range(.., BeatsMap::chmax(4));
+/// let node = segtree.prod(..);
+/// assert_eq!(node.sum, 4 + 4 + 5 + 4 + 4);
+/// assert_eq!(node.max, 5);
+/// assert_eq!(node.min, 4);
+/// ```
+///
+/// [`LazySegtree`]: ../lazysegtree/struct.LazySegtree.html
+pub type SegtreeBeats<T> = LazySegtree<RangeChminChmaxAdd<T>>;
+
+/// The summary of a range in [`SegtreeBeats`].
+///
+/// For the empty range, `sum` is $0$, `max` is `T::min_value()` and `min` is `T::max_value()`.
+///
+/// [`SegtreeBeats`]: ./type.SegtreeBeats.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BeatsNode<T> {
+    pub sum: T,
+    pub max: T,
+    pub min: T,
+    // `second_max` and `second_min` are meaningful only if `min != max`.
+    second_max: T,
+    second_min: T,
+    max_count: T,
+    min_count: T,
+    len: T,
+}
+
+impl<T: Integral> BeatsNode<T> {
+    /// Returns the node for a single element $x$.
+    pub fn new(x: T) -> Self {
+        BeatsNode {
+            sum: x,
+            max: x,
+            min: x,
+            second_max: T::min_value(),
+            second_min: T::max_value(),
+            max_count: T::one(),
+            min_count: T::one(),
+            len: T::one(),
+        }
+    }
+
+    fn add(&mut self, a: T) {
+        self.sum += a * self.len;
+        self.max += a;
+        self.min += a;
+        if self.min != self.max {
+            self.second_max += a;
+            self.second_min += a;
+        }
+    }
+
+    fn fill(&mut self, x: T) {
+        self.sum = x * self.len;
+        self.max = x;
+        self.min = x;
+    }
+
+    fn chmax(&mut self, x: T) -> bool {
+        if x <= self.min {
+            true
+        } else if self.min == self.max {
+            self.fill(x);
+            true
+        } else if x < self.second_min {
+            self.sum += (x - self.min) * self.min_count;
+            if self.second_max == self.min {
+                self.second_max = x;
+            }
+            self.min = x;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn chmin(&mut self, x: T) -> bool {
+        if self.max <= x {
+            true
+        } else if self.min == self.max {
+            self.fill(x);
+            true
+        } else if self.second_max < x {
+            self.sum -= (self.max - x) * self.max_count;
+            if self.second_min == self.max {
+                self.second_min = x;
+            }
+            self.max = x;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// The monoid of [`BeatsNode`]s.
+///
+/// [`BeatsNode`]: ./struct.BeatsNode.html
+pub struct BeatsMonoid<T>(Infallible, PhantomData<fn() -> T>);
+impl<T: Integral> Monoid for BeatsMonoid<T> {
+    type S = BeatsNode<T>;
+    fn identity() -> Self::S {
+        BeatsNode {
+            sum: T::zero(),
+            max: T::min_value(),
+            min: T::max_value(),
+            second_max: T::min_value(),
+            second_min: T::max_value(),
+            max_count: T::zero(),
+            min_count: T::zero(),
+            len: T::zero(),
+        }
+    }
+    fn binary_operation(a: &Self::S, b: &Self::S) -> Self::S {
+        if a.len == T::zero() {
+            return *b;
+        }
+        if b.len == T::zero() {
+            return *a;
+        }
+        // Only the values that actually appear in `a` or `b`.
+        let values = |x: &BeatsNode<T>| {
+            if x.min == x.max {
+                [x.min, x.min, x.max, x.max]
+            } else {
+                [x.min, x.second_min, x.second_max, x.max]
+            }
+        };
+        let (va, vb) = (values(a), values(b));
+        let max = a.max.max(b.max);
+        let min = a.min.min(b.min);
+        let mut ret = BeatsNode {
+            sum: a.sum + b.sum,
+            max,
+            min,
+            second_max: T::min_value(),
+            second_min: T::max_value(),
+            max_count: T::zero(),
+            min_count: T::zero(),
+            len: a.len + b.len,
+        };
+        for (x, v) in [(a, &va), (b, &vb)].iter() {
+            if x.max == max {
+                ret.max_count += x.max_count;
+            }
+            if x.min == min {
+                ret.min_count += x.min_count;
+            }
+            for &y in v.iter() {
+                if y < max {
+                    ret.second_max = ret.second_max.max(y);
+                }
+                if y > min {
+                    ret.second_min = ret.second_min.min(y);
+                }
+            }
+        }
+        ret
+    }
+}
+
+/// The map $x \mapsto \min(\max(x + a, l), h)$ used by [`SegtreeBeats`].
+///
+/// [`SegtreeBeats`]: ./type.SegtreeBeats.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BeatsMap<T> {
+    add: T,
+    lower: T,
+    upper: T,
+}
+
+impl<T: Integral> BeatsMap<T> {
+    /// Returns $x \mapsto x$.
+    pub fn identity() -> Self {
+        BeatsMap {
+            add: T::zero(),
+            lower: T::min_value(),
+            upper: T::max_value(),
+        }
+    }
+
+    /// Returns $x \mapsto \min(x, a)$.
+    pub fn chmin(a: T) -> Self {
+        BeatsMap {
+            upper: a,
+            ..Self::identity()
+        }
+    }
+
+    /// Returns $x \mapsto \max(x, a)$.
+    pub fn chmax(a: T) -> Self {
+        BeatsMap {
+            lower: a,
+            ..Self::identity()
+        }
+    }
+
+    /// Returns $x \mapsto x + a$.
+    pub fn add(a: T) -> Self {
+        BeatsMap {
+            add: a,
+            ..Self::identity()
+        }
+    }
+
+    /// Returns $f(x)$.
+    pub fn apply(&self, x: T) -> T {
+        (x + self.add).max(self.lower).min(self.upper)
+    }
+}
+
+/// The [`MapMonoid`] of [`SegtreeBeats`].
+///
+/// [`MapMonoid`]: ../lazysegtree/trait.MapMonoid.html
+/// [`SegtreeBeats`]: ./type.SegtreeBeats.html
+pub struct RangeChminChmaxAdd<T>(Infallible, PhantomData<fn() -> T>);
+impl<T: Integral> MapMonoid for RangeChminChmaxAdd<T> {
+    type M = BeatsMonoid<T>;
+    type F = BeatsMap<T>;
+
+    fn identity_map() -> Self::F {
+        BeatsMap::identity()
+    }
+
+    fn mapping(f: &Self::F, x: &BeatsNode<T>) -> BeatsNode<T> {
+        Self::try_mapping(f, x).expect("the mapping failed on an element")
+    }
+
+    fn try_mapping(f: &Self::F, x: &BeatsNode<T>) -> Option<BeatsNode<T>> {
+        let mut x = *x;
+        if x.len == T::zero() {
+            return Some(x);
+        }
+        x.add(f.add);
+        if x.chmax(f.lower) && x.chmin(f.upper) {
+            Some(x)
+        } else {
+            None
+        }
+    }
+
+    // `f` after `g`
+    fn composition(f: &Self::F, g: &Self::F) -> Self::F {
+        let shift = |x: T, bound: T| if x == bound { x } else { x + f.add };
+        let clamp = |x: T| x.max(f.lower).min(f.upper);
+        BeatsMap {
+            add: g.add + f.add,
+            lower: clamp(shift(g.lower, T::min_value())),
+            upper: clamp(shift(g.upper, T::max_value())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    #[test]
+    fn test_segtree_beats() {
+        let mut rng = rand::thread_rng();
+        for &n in &[0, 1, 2, 7, 16, 50] {
+            let mut naive = (0..n)
+                .map(|_| rng.gen_range(-100, 100))
+                .collect::<Vec<i64>>();
+            let mut segtree = SegtreeBeats::<i64>::from(
+                naive.iter().map(|&x| BeatsNode::new(x)).collect::<Vec<_>>(),
+            );
+            for _ in 0..1000 {
+                let l = rng.gen_range(0, n + 1);
+                let r = rng.gen_range(l, n + 1);
+                let a = rng.gen_range(-100, 100);
+                let f = match rng.gen_range(0, 5) {
+                    0 => BeatsMap::chmin(a),
+                    1 => BeatsMap::chmax(a),
+                    2 => BeatsMap::add(a / 10),
+                    3 => {
+                        let p = rng.gen_range(0, n.max(1));
+                        if p < n {
+                            naive[p] = a;
+                            segtree.set(p, BeatsNode::new(a));
+                        }
+                        continue;
+                    }
+                    _ => {
+                        let node = segtree.prod(l..r);
+                        let slice = &naive[l..r];
+                        assert_eq!(node.sum, slice.iter().sum::<i64>());
+                        assert_eq!(node.max, slice.iter().copied().max().unwrap_or(i64::MIN));
+                        assert_eq!(node.min, slice.iter().copied().min().unwrap_or(i64::MAX));
+                        continue;
+                    }
+                };
+                for x in &mut naive[l..r] {
+                    *x = f.apply(*x);
+                }
+                segtree.apply_range(l..r, f);
+            }
+            for (i, &x) in naive.iter().enumerate() {
+                assert_eq!(segtree.get(i).sum, x);
+            }
+        }
+    }
+
+    #[test]
+    fn test_composition() {
+        let mut rng = rand::thread_rng();
+        let mut random_map = || match rng.gen_range(0, 3) {
+            0 => BeatsMap::chmin(rng.gen_range(-20, 20)),
+            1 => BeatsMap::chmax(rng.gen_range(-20, 20)),
+            _ => BeatsMap::add(rng.gen_range(-20, 20)),
+        };
+        for _ in 0..100 {
+            let fs = (0..4).map(|_| random_map()).collect::<Vec<BeatsMap<i32>>>();
+            let composed = fs.iter().fold(BeatsMap::identity(), |g, f| {
+                RangeChminChmaxAdd::composition(f, &g)
+            });
+            for x in -100..100 {
+                assert_eq!(composed.apply(x), fs.iter().fold(x, |x, f| f.apply(x)));
+            }
+        }
+    }
+}
@@ -0,0 +1,267 @@
+//! Static range products in $O(1)$ time per query.
+
+use crate::internal_type_traits::{BoundedAbove, BoundedBelow, Zero};
+use crate::segtree::{ArgMax, ArgMin, BitwiseAnd, BitwiseOr, Gcd, Max, Min, Monoid, Reversed};
+use std::iter::FromIterator;
+use std::ops::{BitAnd, BitOr, Bound, Not, RangeBounds, Rem};
+
+/// A [`Monoid`] whose elements satisfy $x \cdot x = x$.
+///
+/// [`Monoid`]: ../segtree/trait.Monoid.html
+pub trait Idempotent: Monoid {}
+
+impl<S: Copy + Ord + BoundedBelow> Idempotent for Max<S> {}
+impl<S: Copy + Ord + BoundedAbove> Idempotent for Min<S> {}
+impl<S: Copy + Ord + BoundedBelow> Idempotent for ArgMax<S> {}
+impl<S: Copy + Ord + BoundedAbove> Idempotent for ArgMin<S> {}
+impl<S: Copy + BitOr<Output = S> + Zero> Idempotent for BitwiseOr<S> {}
+impl<S: Copy + BitAnd<Output = S> + Not<Output = S> + Zero> Idempotent for BitwiseAnd<S> {}
+impl<S: Copy + Eq + Rem<Output = S> + Zero> Idempotent for Gcd<S> {}
+impl<M: Idempotent> Idempotent for Reversed<M> {}
+impl<M1: Idempotent, M2: Idempotent> Idempotent for (M1, M2) {}
+
+/// A sparse table for an idempotent monoid.
+///
+/// `table[k][i]` holds the product of $[i, i + 2^k)$, and a query combines two overlapping blocks.
+///
+/// # Complexity
+///
+/// - $O(n \log n)$ to build
+/// - $O(1)$ for each `prod`
+///
+/// # Example
+///
+/// ```
+/// use ac_library::{Min, SparseTable};
+///
+/// let table: SparseTable<Min<i32>> = vec![3, 1, 4, 1, 5, 9, 2, 6].into();
+/// assert_eq!(table.prod(..), 1);
+/// assert_eq!(table.prod(4..7), 2);
+/// assert_eq!(table.prod(5..=5), 9);
+/// assert_eq!(table.prod(3..3), i32::MAX);
+/// ```
+#[derive(Clone)]
+pub struct SparseTable<M>
+where
+    M: Idempotent,
+{
+    n: usize,
+    table: Vec<Vec<M::S>>,
+}
+
+impl<M: Idempotent> From<Vec<M::S>> for SparseTable<M> {
+    fn from(v: Vec<M::S>) -> Self {
+        let n = v.len();
+        let mut table = vec![v];
+        let mut k = 1;
+        while 2 * k <= n {
+            let prev = table.last().unwrap();
+            let next = (0..=n - 2 * k)
+                .map(|i| M::binary_operation(&prev[i], &prev[i + k]))
+                .collect();
+            table.push(next);
+            k *= 2;
+        }
+        SparseTable { n, table }
+    }
+}
+impl<M: Idempotent> FromIterator<M::S> for SparseTable<M> {
+    fn from_iter<T: IntoIterator<Item = M::S>>(iter: T) -> Self {
+        iter.into_iter().collect::<Vec<_>>().into()
+    }
+}
+impl<M: Idempotent> SparseTable<M> {
+    /// Returns the product of the elements in `range`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is out of bounds.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(1)$
+    pub fn prod<R>(&self, range: R) -> M::S
+    where
+        R: RangeBounds<usize>,
+    {
+        let (l, r) = to_pair(range, self.n);
+        if l == r {
+            return M::identity();
+        }
+        let k = floor_log2(r - l);
+        M::binary_operation(&self.table[k][l], &self.table[k][r - (1 << k)])
+    }
+}
+
+/// A disjoint sparse table for any monoid.
+///
+/// For each level $k$, the array is split into blocks of length $2^{k + 1}$, and it holds the products from the center of each block towards both ends.
+/// A query $[l, r)$ multiplies the two products at the level where $l$ and $r - 1$ are in the same block but on different sides of the center.
+///
+/// # Complexity
+///
+/// - $O(n \log n)$ to build
+/// - $O(1)$ for each `prod`
+///
+/// # Example
+///
+/// ```
+/// use ac_library::{Additive, DisjointSparseTable};
+///
+/// let table: DisjointSparseTable<Additive<i32>> = vec![3, 1, 4, 1, 5, 9, 2, 6].into();
+/// assert_eq!(table.prod(..), 31);
+/// assert_eq!(table.prod(2..6), 19);
+/// assert_eq!(table.prod(3..3), 0);
+/// ```
+#[derive(Clone)]
+pub struct DisjointSparseTable<M>
+where
+    M: Monoid,
+{
+    n: usize,
+    table: Vec<Vec<M::S>>,
+}
+
+impl<M: Monoid> From<Vec<M::S>> for DisjointSparseTable<M> {
+    fn from(mut v: Vec<M::S>) -> Self {
+        let n = v.len();
+        let size = n.next_power_of_two().max(2);
+        v.resize(size, M::identity());
+        let mut table = vec![v.clone()];
+        let mut half = 2;
+        while half < size {
+            let mut t = v.clone();
+            for c in (half..size).step_by(2 * half) {
+                for i in (c - half..c - 1).rev() {
+                    t[i] = M::binary_operation(&v[i], &t[i + 1]);
+                }
+                for i in c + 1..c + half {
+                    t[i] = M::binary_operation(&t[i - 1], &v[i]);
+                }
+            }
+            table.push(t);
+            half *= 2;
+        }
+        DisjointSparseTable { n, table }
+    }
+}
+impl<M: Monoid> FromIterator<M::S> for DisjointSparseTable<M> {
+    fn from_iter<T: IntoIterator<Item = M::S>>(iter: T) -> Self {
+        iter.into_iter().collect::<Vec<_>>().into()
+    }
+}
+impl<M: Monoid> DisjointSparseTable<M> {
+    /// Returns the product of the elements in `range`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is out of bounds.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(1)$
+    pub fn prod<R>(&self, range: R) -> M::S
+    where
+        R: RangeBounds<usize>,
+    {
+        let (l, r) = to_pair(range, self.n);
+        if l == r {
+            return M::identity();
+        }
+        let r = r - 1;
+        if l == r {
+            return self.table[0][l].clone();
+        }
+        let k = floor_log2(l ^ r);
+        M::binary_operation(&self.table[k][l], &self.table[k][r])
+    }
+}
+
+fn to_pair<R: RangeBounds<usize>>(range: R, n: usize) -> (usize, usize) {
+    let r = match range.end_bound() {
+        Bound::Included(r) => r + 1,
+        Bound::Excluded(r) => *r,
+        Bound::Unbounded => n,
+    };
+    let l = match range.start_bound() {
+        Bound::Included(l) => *l,
+        Bound::Excluded(l) => l + 1,
+        Bound::Unbounded => 0,
+    };
+    assert!(l <= r && r <= n);
+    (l, r)
+}
+
+fn floor_log2(x: usize) -> usize {
+    (usize::BITS - 1 - x.leading_zeros()) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::segtree::{Additive, Affine};
+    use crate::ModInt998244353;
+    use rand::Rng;
+    use std::ops::Bound::*;
+
+    #[test]
+    fn test_sparse_table() {
+        let mut rng = rand::thread_rng();
+        for n in 0..40 {
+            let v = (0..n).map(|_| rng.gen_range(0, 100)).collect::<Vec<i32>>();
+            let min: SparseTable<Min<i32>> = v.clone().into();
+            let or: SparseTable<BitwiseOr<i32>> = v.iter().copied().collect();
+            let argmax: SparseTable<ArgMax<i32>> = v.iter().copied().zip(0..).collect();
+            for l in 0..=n {
+                for r in l..=n {
+                    let s = &v[l..r];
+                    assert_eq!(min.prod(l..r), s.iter().copied().min().unwrap_or(i32::MAX));
+                    assert_eq!(or.prod(l..r), s.iter().fold(0, |a, &x| a | x));
+                    let expected = s
+                        .iter()
+                        .zip(l..)
+                        .fold(
+                            (i32::MIN, usize::MAX),
+                            |a, (&x, i)| if x > a.0 { (x, i) } else { a },
+                        );
+                    assert_eq!(argmax.prod(l..r), expected);
+                    if r < n {
+                        assert_eq!(
+                            min.prod((Excluded(l), Included(r))),
+                            v[l + 1..=r].iter().copied().min().unwrap_or(i32::MAX)
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_disjoint_sparse_table() {
+        type Z = ModInt998244353;
+        let mut rng = rand::thread_rng();
+        for n in 0..40 {
+            let v = (0..n)
+                .map(|_| (Z::new(rng.gen_range(0, 100)), Z::new(rng.gen_range(0, 100))))
+                .collect::<Vec<_>>();
+            let sum: DisjointSparseTable<Additive<u32>> = v.iter().map(|x| x.0.val()).collect();
+            let affine: DisjointSparseTable<Affine<Z>> = v.clone().into();
+            for l in 0..=n {
+                for r in l..=n {
+                    let s = &v[l..r];
+                    assert_eq!(sum.prod(l..r), s.iter().map(|x| x.0.val()).sum());
+                    let (a, b) = affine.prod(l..r);
+                    let x = Z::new(12345);
+                    assert_eq!(a * x + b, s.iter().fold(x, |x, &(a, b)| a * x + b));
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_out_of_range() {
+        let table: SparseTable<Max<i32>> = vec![1, 2, 3].into();
+        table.prod(1..4);
+    }
+}
@@ -0,0 +1,278 @@
+//! The universal Euclidean algorithm and generalized floor sums.
+
+use crate::internal_math;
+use crate::segtree::Monoid;
+use std::{
+    convert::Infallible,
+    marker::PhantomData,
+    ops::{Add, Mul},
+};
+
+/// Computes the product of the word generated by the line $y = (a x + b) / m$ over a monoid (the "universal Euclidean algorithm").
+///
+/// Walking $i$ from $0$ to $n - 1$, the word appends $u$ until the number of $u$ equals $\left\lfloor \frac{a \times i + b}{m} \right\rfloor$ and then appends $r$.
+/// In other words, it returns
+///
+/// \\[
+///   u^{f(0)} r u^{f(1) - f(0)} r \cdots u^{f(n - 1) - f(n - 2)} r, \quad f(i) = \left\lfloor \frac{a \times i + b}{m} \right\rfloor.
+/// \\]
+///
+/// Choosing a monoid which tracks the number of $u$ and $r$ lets you compute sums such as $\sum_i i^p f(i)^q$.
+/// [`floor_sums`] is an example.
+///
+/// # Constraints
+///
+/// - $1 \leq m$
+///
+/// # Panics
+///
+/// Panics if the above constraints are not satisfied.
+///
+/// # Complexity
+///
+/// - $O(\log^2 (m + a + b))$ calls of `M::binary_operation`
+///
+/// # Example
+///
+/// ```
+/// use ac_library::{universal_euclidean::universal_euclidean, Additive};
+///
+/// // with u = 1 and r = 0, the product is f(n - 1)
+/// assert_eq!(universal_euclidean::<Additive<u64>>(6, 5, 4, 3, 1, 0), 4);
+/// ```
+///
+/// [`floor_sums`]: ./fn.floor_sums.html
+pub fn universal_euclidean<M: Monoid>(n: u64, m: u64, a: u64, b: u64, u: M::S, r: M::S) -> M::S {
+    assert!(1 <= m);
+    if n == 0 {
+        return M::identity();
+    }
+    let prefix = monoid_pow::<M>(&u, b / m);
+    let prefix = M::binary_operation(&prefix, &r);
+    let rest = universal_euclidean_rec::<M>(a, m, b % m, n - 1, u, r);
+    M::binary_operation(&prefix, &rest)
+}
+
+/// Returns the word for $x = 1, 2, \ldots, l$ with $f(x) = \lfloor (p x + r) / q \rfloor$ and $0 \leq r < q$.
+#[allow(clippy::many_single_char_names)]
+fn universal_euclidean_rec<M: Monoid>(p: u64, q: u64, r: u64, l: u64, u: M::S, s: M::S) -> M::S {
+    if p >= q {
+        let s = M::binary_operation(&monoid_pow::<M>(&u, p / q), &s);
+        return universal_euclidean_rec::<M>(p % q, q, r, l, u, s);
+    }
+    let m = ((p as u128 * l as u128 + r as u128) / q as u128) as u64;
+    if m == 0 {
+        return monoid_pow::<M>(&s, l);
+    }
+    // the k-th `u` (1-indexed) appears just before the `s` for x = floor((q k - r - 1) / p) + 1
+    let head = monoid_pow::<M>(&s, (q - r - 1) / p);
+    let tail = monoid_pow::<M>(
+        &s,
+        l - ((q as u128 * m as u128 - r as u128 - 1) / p as u128) as u64,
+    );
+    let head = M::binary_operation(&head, &u);
+    let mid = universal_euclidean_rec::<M>(q, p, (q - r - 1) % p, m - 1, s, u);
+    M::binary_operation(&M::binary_operation(&head, &mid), &tail)
+}
+
+fn monoid_pow<M: Monoid>(x: &M::S, mut n: u64) -> M::S {
+    let mut r = M::identity();
+    let mut y = x.clone();
+    while n != 0 {
+        if n & 1 != 0 {
+            r = M::binary_operation(&r, &y);
+        }
+        y = M::binary_operation(&y, &y);
+        n >>= 1;
+    }
+    r
+}
+
+/// Returns
+///
+/// \\[
+///   \left( \sum_{i = 0}^{n - 1} f(i), \sum_{i = 0}^{n - 1} i f(i), \sum_{i = 0}^{n - 1} f(i)^2 \right), \quad f(i) = \left\lfloor \frac{a \times i + b}{m} \right\rfloor.
+/// \\]
+///
+/// `T` is typically `i128` or a [`ModIntBase`].
+///
+/// # Constraints
+///
+/// - $0 \leq n$
+/// - $1 \leq m$
+/// - If `T` is a primitive integer, every partial sum fits in `T`
+///
+/// # Panics
+///
+/// Panics if the above constraints are not satisfied and overflow occurred.
+///
+/// # Complexity
+///
+/// - $O(\log^2 (m + |a| + |b|))$
+///
+/// # Example
+///
+/// ```
+/// use ac_library::{universal_euclidean::floor_sums, ModInt998244353 as Mint};
+///
+/// assert_eq!(floor_sums::<i128>(6, 5, 4, 3), (13, 46, 39));
+/// assert_eq!(
+///     floor_sums::<Mint>(1_000_000_000, 1, 1, 0).2,
+///     Mint::new(333_333_332_833_333_333_500_000_000u128),
+/// );
+/// ```
+///
+/// [`ModIntBase`]: ../modint/trait.ModIntBase.html
+#[allow(clippy::many_single_char_names)]
+pub fn floor_sums<T>(n: i64, m: i64, a: i64, b: i64) -> (T, T, T)
+where
+    T: Copy + From<i64> + Add<Output = T> + Mul<Output = T>,
+{
+    assert!(0 <= n && 1 <= m);
+    let (a2, b2) = (internal_math::safe_mod(a, m), internal_math::safe_mod(b, m));
+    // f(i) = floor((a2 i + b2) / m) + ka i + kb
+    let (ka, kb) = ((a - a2) / m, (b - b2) / m);
+    let up = |k: i64| FloorSumsNode {
+        y: T::from(k),
+        ..FloorSumsNode::zero()
+    };
+    let right = FloorSumsNode {
+        x: T::from(1),
+        ..FloorSumsNode::zero()
+    };
+    let right = FloorSumsMonoid::binary_operation(&right, &up(ka));
+    let prod = universal_euclidean::<FloorSumsMonoid<T>>(
+        n as u64,
+        m as u64,
+        a2 as u64,
+        b2 as u64,
+        up(1),
+        right,
+    );
+    let prod = FloorSumsMonoid::binary_operation(&up(kb), &prod);
+    (prod.sy, prod.sxy, prod.syy)
+}
+
+/// `(x, y)` is the displacement of a word, and `sx`, `sy`, ... are the sums over each `r` of the coordinates just before it.
+#[derive(Clone, Copy)]
+struct FloorSumsNode<T> {
+    x: T,
+    y: T,
+    sx: T,
+    sy: T,
+    sxy: T,
+    syy: T,
+}
+
+impl<T: From<i64>> FloorSumsNode<T> {
+    fn zero() -> Self {
+        Self {
+            x: T::from(0),
+            y: T::from(0),
+            sx: T::from(0),
+            sy: T::from(0),
+            sxy: T::from(0),
+            syy: T::from(0),
+        }
+    }
+}
+
+struct FloorSumsMonoid<T>(Infallible, PhantomData<fn() -> T>);
+
+impl<T> Monoid for FloorSumsMonoid<T>
+where
+    T: Copy + From<i64> + Add<Output = T> + Mul<Output = T>,
+{
+    type S = FloorSumsNode<T>;
+    fn identity() -> Self::S {
+        FloorSumsNode::zero()
+    }
+    fn binary_operation(a: &Self::S, b: &Self::S) -> Self::S {
+        let two = T::from(2);
+        FloorSumsNode {
+            x: a.x + b.x,
+            y: a.y + b.y,
+            sx: a.sx + b.sx + a.x * b.x,
+            sy: a.sy + b.sy + a.y * b.x,
+            sxy: a.sxy + b.sxy + a.x * b.sy + a.y * b.sx + a.x * a.y * b.x,
+            syy: a.syy + b.syy + two * a.y * b.sy + a.y * a.y * b.x,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::floor_sum;
+
+    #[test]
+    #[allow(clippy::many_single_char_names)]
+    fn test_floor_sums() {
+        type Mint = crate::ModInt998244353;
+
+        for n in 0..15 {
+            for m in 1..15 {
+                for a in -15..15 {
+                    for b in -15..15 {
+                        let f = |i: i64| (a * i + b).div_euclid(m) as i128;
+                        let expected = (0..n as i128).fold((0, 0, 0), |(s, si, ss), i| {
+                            let y = f(i as i64);
+                            (s + y, si + i * y, ss + y * y)
+                        });
+                        assert_eq!(floor_sums::<i128>(n, m, a, b), expected);
+                        let (s, si, ss) = floor_sums::<Mint>(n, m, a, b);
+                        assert_eq!(
+                            (s, si, ss),
+                            (
+                                Mint::from(expected.0 as i64),
+                                Mint::from(expected.1 as i64),
+                                Mint::from(expected.2 as i64)
+                            )
+                        );
+                    }
+                }
+            }
+        }
+        let (n, m, a, b) = (1 << 31, (1 << 31) - 1, 1 << 30, 12345);
+        assert_eq!(
+            floor_sums::<i128>(n, m, a, b).0,
+            floor_sum(n, m, a, b) as i128
+        );
+    }
+
+    #[test]
+    fn test_universal_euclidean() {
+        // the word itself, as a string
+        struct Concat;
+        impl Monoid for Concat {
+            type S = String;
+            fn identity() -> String {
+                String::new()
+            }
+            fn binary_operation(a: &String, b: &String) -> String {
+                a.clone() + b
+            }
+        }
+        for n in 0..10 {
+            for m in 1..10 {
+                for a in 0..10 {
+                    for b in 0..10 {
+                        let mut expected = String::new();
+                        let mut y = 0;
+                        for i in 0..n {
+                            while y < (a * i + b) / m {
+                                expected.push('U');
+                                y += 1;
+                            }
+                            expected.push('R');
+                        }
+                        assert_eq!(
+                            universal_euclidean::<Concat>(n, m, a, b, "U".into(), "R".into()),
+                            expected
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,420 @@
+//! Wavelet matrix for order statistics on a static sequence of integers.
+
+use std::ops::{Bound, RangeBounds};
+
+/// A wavelet matrix over a sequence of `u64`.
+///
+/// It uses $\lceil \log_2 (\max v + 1) \rceil$ levels, each of which is a bit vector of length $n$.
+///
+/// # Complexity
+///
+/// - $O(n \log V)$ to build
+/// - $O(\log V)$ for each query
+///
+/// where $V$ is the maximum value.
+///
+/// # Example
+///
+/// ```
+/// use ac_library::WaveletMatrix;
+///
+/// let wm = WaveletMatrix::new(&[5, 4, 5, 5, 2, 1, 5, 6, 1, 3, 5, 0]);
+/// assert_eq!(wm.access(3), 5);
+/// assert_eq!(wm.rank(5, 9), 4);
+/// assert_eq!(wm.kth_smallest(2..9, 3), 5);
+/// assert_eq!(wm.range_freq(2..9, 2..6), 4);
+/// assert_eq!(wm.prev_value(2..9, 5), Some(2));
+/// assert_eq!(wm.next_value(2..9, 5), Some(5));
+/// assert_eq!(wm.next_value(2..9, 7), None);
+/// ```
+#[derive(Clone, Debug)]
+pub struct WaveletMatrix {
+    n: usize,
+    // `levels[d]` holds the `(log - 1 - d)`-th bits.
+    levels: Vec<BitVector>,
+    zeros: Vec<usize>,
+}
+
+impl WaveletMatrix {
+    /// Creates a wavelet matrix over `v`.
+    pub fn new(v: &[u64]) -> Self {
+        Self::build(v, |_, _| {})
+    }
+
+    /// Returns the length of the sequence.
+    pub fn len(&self) -> usize {
+        self.n
+    }
+
+    /// Returns whether the sequence is empty.
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    /// Returns the $i$-th element.
+    pub fn access(&self, mut i: usize) -> u64 {
+        assert!(i < self.n);
+        let mut ret = 0;
+        for (level, &zeros) in self.levels.iter().zip(&self.zeros) {
+            ret <<= 1;
+            if level.get(i) {
+                ret |= 1;
+                i = zeros + level.rank1(i);
+            } else {
+                i = level.rank0(i);
+            }
+        }
+        ret
+    }
+
+    /// Returns the number of occurrences of $x$ in $[0, r)$.
+    pub fn rank(&self, x: u64, r: usize) -> usize {
+        assert!(r <= self.n);
+        if !self.in_range(x) {
+            return 0;
+        }
+        let (mut l, mut r) = (0, r);
+        for (d, (level, &zeros)) in self.levels.iter().zip(&self.zeros).enumerate() {
+            if x >> (self.levels.len() - 1 - d) & 1 == 1 {
+                l = zeros + level.rank1(l);
+                r = zeros + level.rank1(r);
+            } else {
+                l = level.rank0(l);
+                r = level.rank0(r);
+            }
+        }
+        r - l
+    }
+
+    /// Returns the $k$-th smallest value (0-indexed) in `range`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is out of bounds or $k$ is not less than its length.
+    pub fn kth_smallest<R: RangeBounds<usize>>(&self, range: R, k: usize) -> u64 {
+        let (l, r) = to_pair(range, self.n);
+        assert!(k < r - l);
+        self.kth_smallest_impl(l, r, k, |_, _, _| {}).0
+    }
+
+    /// Returns the $k$-th largest value (0-indexed) in `range`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is out of bounds or $k$ is not less than its length.
+    pub fn kth_largest<R: RangeBounds<usize>>(&self, range: R, k: usize) -> u64 {
+        let (l, r) = to_pair(range, self.n);
+        assert!(k < r - l);
+        self.kth_smallest_impl(l, r, r - l - 1 - k, |_, _, _| {}).0
+    }
+
+    /// Returns the number of the elements in `range` whose values are in `values`.
+    pub fn range_freq<R1, R2>(&self, range: R1, values: R2) -> usize
+    where
+        R1: RangeBounds<usize>,
+        R2: RangeBounds<u64>,
+    {
+        let (l, r) = to_pair(range, self.n);
+        let lower = match values.start_bound() {
+            Bound::Included(&x) => u128::from(x),
+            Bound::Excluded(&x) => u128::from(x) + 1,
+            Bound::Unbounded => 0,
+        };
+        let upper = match values.end_bound() {
+            Bound::Included(&x) => u128::from(x) + 1,
+            Bound::Excluded(&x) => u128::from(x),
+            Bound::Unbounded => u128::from(u64::MAX) + 1,
+        };
+        if lower >= upper {
+            return 0;
+        }
+        self.count_less(l, r, upper) - self.count_less(l, r, lower)
+    }
+
+    /// Returns the largest value less than $x$ in `range`, if any.
+    pub fn prev_value<R: RangeBounds<usize>>(&self, range: R, x: u64) -> Option<u64> {
+        let (l, r) = to_pair(range, self.n);
+        match self.count_less(l, r, x.into()) {
+            0 => None,
+            k => Some(self.kth_smallest_impl(l, r, k - 1, |_, _, _| {}).0),
+        }
+    }
+
+    /// Returns the smallest value at least $x$ in `range`, if any.
+    pub fn next_value<R: RangeBounds<usize>>(&self, range: R, x: u64) -> Option<u64> {
+        let (l, r) = to_pair(range, self.n);
+        match self.count_less(l, r, x.into()) {
+            k if k == r - l => None,
+            k => Some(self.kth_smallest_impl(l, r, k, |_, _, _| {}).0),
+        }
+    }
+
+    /// Builds the levels, calling `f(d, &v)` with the sequence before the $d$-th level is applied, for $d = 0, 1, \ldots, \log$.
+    fn build(v: &[u64], mut f: impl FnMut(usize, &[u64])) -> Self {
+        let n = v.len();
+        let log = v.iter().max().map_or(0, |&x| 64 - x.leading_zeros()) as usize;
+        let mut cur = v.to_vec();
+        let mut levels = Vec::with_capacity(log);
+        let mut zeros = Vec::with_capacity(log);
+        for d in 0..log {
+            f(d, &cur);
+            let bit = log - 1 - d;
+            let level = BitVector::new(cur.iter().map(|&x| x >> bit & 1 == 1));
+            let (mut next, ones): (Vec<_>, Vec<_>) = cur.iter().partition(|&&x| x >> bit & 1 == 0);
+            zeros.push(next.len());
+            next.extend(ones);
+            levels.push(level);
+            cur = next;
+        }
+        f(log, &cur);
+        WaveletMatrix { n, levels, zeros }
+    }
+
+    fn in_range(&self, x: u64) -> bool {
+        self.levels.len() == 64 || x >> self.levels.len() == 0
+    }
+
+    /// Returns the number of the elements in $[l, r)$ less than $x$.
+    fn count_less(&self, mut l: usize, mut r: usize, x: u128) -> usize {
+        if x >> self.levels.len() != 0 {
+            return r - l;
+        }
+        let mut ret = 0;
+        for (d, (level, &zeros)) in self.levels.iter().zip(&self.zeros).enumerate() {
+            if x >> (self.levels.len() - 1 - d) & 1 == 1 {
+                ret += level.rank0(r) - level.rank0(l);
+                l = zeros + level.rank1(l);
+                r = zeros + level.rank1(r);
+            } else {
+                l = level.rank0(l);
+                r = level.rank0(r);
+            }
+        }
+        ret
+    }
+
+    /// Returns the $k$-th smallest value $x$ and the number of the elements equal to $x$ among the $k + 1$ smallest ones.
+    ///
+    /// It calls `f(d, l0, r0)` whenever it skips the smaller elements, which are at $[l_0, r_0)$ before the $d$-th level.
+    fn kth_smallest_impl(
+        &self,
+        mut l: usize,
+        mut r: usize,
+        mut k: usize,
+        mut f: impl FnMut(usize, usize, usize),
+    ) -> (u64, usize) {
+        let mut ret = 0;
+        for (d, (level, &zeros)) in self.levels.iter().zip(&self.zeros).enumerate() {
+            let (l0, r0) = (level.rank0(l), level.rank0(r));
+            ret <<= 1;
+            if k < r0 - l0 {
+                l = l0;
+                r = r0;
+            } else {
+                f(d + 1, l0, r0);
+                k -= r0 - l0;
+                ret |= 1;
+                l = zeros + (l - l0);
+                r = zeros + (r - r0);
+            }
+        }
+        (ret, k + 1)
+    }
+}
+
+/// A [`WaveletMatrix`] with the prefix sums of each level, which also answers the sum of the $k$ smallest values in a range.
+///
+/// It uses $O(n \log V)$ `u128`s in addition to the [`WaveletMatrix`].
+///
+/// # Example
+///
+/// ```
+/// use ac_library::WaveletMatrixWithSum;
+///
+/// let wm = WaveletMatrixWithSum::new(&[5, 4, 5, 5, 2, 1, 5, 6, 1, 3, 5, 0]);
+/// assert_eq!(wm.sum_smallest(2..9, 3), 1 + 1 + 2);
+/// assert_eq!(wm.sum_smallest(.., 12), 42);
+/// assert_eq!(wm.matrix().kth_smallest(.., 11), 6);
+/// ```
+///
+/// [`WaveletMatrix`]: ./struct.WaveletMatrix.html
+#[derive(Clone, Debug)]
+pub struct WaveletMatrixWithSum {
+    matrix: WaveletMatrix,
+    // `sums[d][i]` is the sum of the first `i` elements before the `d`-th level.
+    sums: Vec<Vec<u128>>,
+}
+
+impl WaveletMatrixWithSum {
+    /// Creates a wavelet matrix over `v`.
+    pub fn new(v: &[u64]) -> Self {
+        let mut sums = vec![];
+        let matrix = WaveletMatrix::build(v, |_, cur| {
+            let mut s = vec![0; cur.len() + 1];
+            for (i, &x) in cur.iter().enumerate() {
+                s[i + 1] = s[i] + u128::from(x);
+            }
+            sums.push(s);
+        });
+        WaveletMatrixWithSum { matrix, sums }
+    }
+
+    /// Returns the underlying [`WaveletMatrix`].
+    ///
+    /// [`WaveletMatrix`]: ./struct.WaveletMatrix.html
+    pub fn matrix(&self) -> &WaveletMatrix {
+        &self.matrix
+    }
+
+    /// Returns the sum of the $k$ smallest values in `range`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is out of bounds or $k$ is greater than its length.
+    pub fn sum_smallest<R: RangeBounds<usize>>(&self, range: R, k: usize) -> u128 {
+        let (l, r) = to_pair(range, self.matrix.n);
+        assert!(k <= r - l);
+        if k == 0 {
+            return 0;
+        }
+        let mut ret = 0;
+        let (x, equal) = self.matrix.kth_smallest_impl(l, r, k - 1, |d, l0, r0| {
+            ret += self.sums[d][r0] - self.sums[d][l0];
+        });
+        ret + equal as u128 * u128::from(x)
+    }
+}
+
+#[derive(Clone, Debug)]
+struct BitVector {
+    bits: Vec<u64>,
+    // `ranks[i]` is the number of ones in `bits[..i]`.
+    ranks: Vec<usize>,
+}
+
+impl BitVector {
+    fn new(it: impl ExactSizeIterator<Item = bool>) -> Self {
+        let mut bits = vec![0; it.len() / 64 + 1];
+        for (i, b) in it.enumerate() {
+            bits[i / 64] |= u64::from(b) << (i % 64);
+        }
+        let mut ranks = vec![0; bits.len() + 1];
+        for (i, &x) in bits.iter().enumerate() {
+            ranks[i + 1] = ranks[i] + x.count_ones() as usize;
+        }
+        BitVector { bits, ranks }
+    }
+
+    fn get(&self, i: usize) -> bool {
+        self.bits[i / 64] >> (i % 64) & 1 == 1
+    }
+
+    /// Returns the number of ones in $[0, i)$.
+    fn rank1(&self, i: usize) -> usize {
+        self.ranks[i / 64] + (self.bits[i / 64] & ((1 << (i % 64)) - 1)).count_ones() as usize
+    }
+
+    fn rank0(&self, i: usize) -> usize {
+        i - self.rank1(i)
+    }
+}
+
+fn to_pair<R: RangeBounds<usize>>(range: R, n: usize) -> (usize, usize) {
+    let r = match range.end_bound() {
+        Bound::Included(r) => r + 1,
+        Bound::Excluded(r) => *r,
+        Bound::Unbounded => n,
+    };
+    let l = match range.start_bound() {
+        Bound::Included(l) => *l,
+        Bound::Excluded(l) => l + 1,
+        Bound::Unbounded => 0,
+    };
+    assert!(l <= r && r <= n);
+    (l, r)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    #[test]
+    fn test_wavelet_matrix() {
+        let mut rng = rand::thread_rng();
+        for &(n, max) in &[
+            (0, 1),
+            (1, 1),
+            (10, 1),
+            (50, 10),
+            (100, 1000),
+            (30, u64::MAX),
+        ] {
+            let v = (0..n)
+                .map(|_| {
+                    if max == u64::MAX && rng.gen() {
+                        u64::MAX - rng.gen_range(0, 3)
+                    } else {
+                        rng.gen_range(0, max)
+                    }
+                })
+                .collect::<Vec<u64>>();
+            let wm = WaveletMatrix::new(&v);
+            let wms = WaveletMatrixWithSum::new(&v);
+            assert_eq!(wm.len(), n);
+            for (i, &x) in v.iter().enumerate() {
+                assert_eq!(wm.access(i), x);
+                assert_eq!(wm.rank(x, i), v[..i].iter().filter(|&&y| y == x).count());
+            }
+            assert_eq!(wm.rank(max, n), v.iter().filter(|&&y| y == max).count());
+            for _ in 0..200 {
+                let l = rng.gen_range(0, n + 1);
+                let r = rng.gen_range(l, n + 1);
+                let mut sorted = v[l..r].to_vec();
+                sorted.sort_unstable();
+                for k in 0..r - l {
+                    assert_eq!(wm.kth_smallest(l..r, k), sorted[k]);
+                    assert_eq!(wm.kth_largest(l..r, k), sorted[r - l - 1 - k]);
+                }
+                for k in 0..=r - l {
+                    let expected = sorted[..k].iter().map(|&x| u128::from(x)).sum::<u128>();
+                    assert_eq!(wms.sum_smallest(l..r, k), expected);
+                }
+                let x = if rng.gen() && l < r {
+                    v[rng.gen_range(l, r)]
+                } else if max == u64::MAX {
+                    rng.gen()
+                } else {
+                    rng.gen_range(0, max + 2)
+                };
+                let y = x.saturating_add(rng.gen_range(0, 10));
+                assert_eq!(
+                    wm.range_freq(l..r, x..y),
+                    sorted.iter().filter(|&&z| x <= z && z < y).count()
+                );
+                assert_eq!(
+                    wm.range_freq(l..r, x..=y),
+                    sorted.iter().filter(|&&z| x <= z && z <= y).count()
+                );
+                assert_eq!(
+                    wm.range_freq(l..r, ..x),
+                    sorted.iter().filter(|&&z| z < x).count()
+                );
+                assert_eq!(
+                    wm.prev_value(l..r, x),
+                    sorted.iter().copied().filter(|&z| z < x).max()
+                );
+                assert_eq!(
+                    wm.next_value(l..r, x),
+                    sorted.iter().copied().filter(|&z| z >= x).min()
+                );
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_kth_smallest_out_of_range() {
+        WaveletMatrix::new(&[1, 2, 3]).kth_smallest(1..2, 1);
+    }
+}